@@ -0,0 +1,18 @@
+#![no_main]
+
+use dmx_rdm_enttec_pro::EnttecMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let message = match EnttecMessage::deserialize(data) {
+        Some(message) => message,
+        None => return,
+    };
+
+    // Everything that is accepted has to be a well-formed message.
+    assert_eq!(message.serialize(), data);
+
+    message.receive_status();
+    message.payload();
+    message.start_code();
+});
@@ -13,52 +13,156 @@
 //! let mut dmx_controller = create_dmx_controller_from_enttec_pro(Ftdi::with_index(0).unwrap()).unwrap();
 //! ```
 
-use dmx_rdm::consts::DMX_NULL_START;
+use dmx_rdm::command_class::RequestCommandClass;
+use dmx_rdm::consts::DMX_MAX_PACKAGE_SIZE;
 use dmx_rdm::dmx_controller::{DmxController, DmxControllerConfig};
 use dmx_rdm::dmx_driver::{
     ControllerDriverErrorDef, CustomStartCodeControllerDriver, DiscoveryOption,
     DmxControllerDriver, DmxError, RdmControllerDriver,
 };
 use dmx_rdm::rdm_data::{deserialize_discovery_response, RdmData, RdmDeserializationError};
+use dmx_rdm::types::NackReason;
 use dmx_rdm::unique_identifier::UniqueIdentifier;
+use dmx_rdm_utils::frame::{validate_dmx_frame, FrameError};
+use dmx_rdm_utils::start_code::StartCode;
+use dmx_rdm_utils::uid::is_plausible_uid;
 use libftd2xx::{FtStatus, Ftdi, FtdiCommon, TimeoutError};
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+mod rdm_requests;
+
+pub use rdm_requests::{SensorValue, ALL_SUB_DEVICES, ROOT_DEVICE};
 
 const ENTTEC_MANUFACTURER_ID: u16 = 0x454E;
+const PLACEHOLDER_RDM_UID: UniqueIdentifier = match UniqueIdentifier::new(ENTTEC_MANUFACTURER_ID, 0)
+{
+    Ok(rdm_uid) => rdm_uid,
+    Err(_) => panic!("invalid placeholder uid"),
+};
 const START_OF_MESSAGE_DELIMITER: u8 = 0x7E;
 const END_OF_MESSAGE_DELIMITER: u8 = 0xE7;
 const MAX_DATA_LENGTH: usize = 600;
 const MIN_PACKAGE_SIZE: usize = 5;
+const DEFAULT_SERIAL_TIMEOUT: Duration = Duration::from_millis(50);
+const RDM_RETRY_DELAY: Duration = Duration::from_millis(10);
+const DEFAULT_RDM_RESPONSE_TIMEOUT: Duration = Duration::from_millis(20);
+// Some widgets drop packages that follow an rdm package too quickly.
+const RDM_SEND_PAUSE: Duration = Duration::from_millis(5);
+const HANDSHAKE_RETRY_DELAY: Duration = Duration::from_millis(100);
+const WIDGET_RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+// the widget outputs 40 frames per second by default
+const WIDGET_FRAME_PERIOD: Duration = Duration::from_millis(25);
 
+const GET_WIDGET_PARAMETERS: u8 = 3;
+const SET_WIDGET_PARAMETERS: u8 = 4;
 const RECEIVED_DMX_PACKET: u8 = 5;
 const SEND_DMX_PACKET_REQUEST: u8 = 6;
 const SEND_RDM_PACKET_REQUEST: u8 = 7;
+const RECEIVE_DMX_ON_CHANGE: u8 = 8;
+const RECEIVED_DMX_CHANGE_OF_STATE_PACKET: u8 = 9;
 const GET_WIDGET_SERIAL_NUMBER: u8 = 10;
 const SEND_RDM_DISCOVERY_REQUEST: u8 = 11;
+// start changed byte number and the changed bit array
+const CHANGE_OF_STATE_HEADER_SIZE: usize = 6;
 
-#[derive(Debug, Clone)]
-struct EnttecMessage {
+/// The payload of an [EnttecMessage]. With the `heapless` feature this is a fixed capacity
+/// buffer, so sending and receiving doesn't allocate.
+#[cfg(feature = "heapless")]
+pub type MessageData = heapless::Vec<u8, MAX_DATA_LENGTH>;
+/// The payload of an [EnttecMessage]. With the `heapless` feature this is a fixed capacity
+/// buffer, so sending and receiving doesn't allocate.
+#[cfg(not(feature = "heapless"))]
+pub type MessageData = Vec<u8>;
+
+/// A serialized [EnttecMessage] including the delimiters.
+#[cfg(feature = "heapless")]
+pub type SerializedMessage = heapless::Vec<u8, { MAX_DATA_LENGTH + MIN_PACKAGE_SIZE }>;
+/// A serialized [EnttecMessage] including the delimiters.
+#[cfg(not(feature = "heapless"))]
+pub type SerializedMessage = Vec<u8>;
+
+/// Copies `data` into a [MessageData]. The length has to be checked beforehand.
+fn to_message_data(data: &[u8]) -> MessageData {
+    #[cfg(feature = "heapless")]
+    {
+        MessageData::from_slice(data).expect("payload is bigger than 600 bytes")
+    }
+    #[cfg(not(feature = "heapless"))]
+    {
+        data.to_vec()
+    }
+}
+
+/// Serializes a message with the given `label` and payload `data` into `buffer` without
+/// building an [EnttecMessage] first.
+fn serialize_message_into(
+    label: u8,
+    data: &[u8],
+    buffer: &mut [u8],
+) -> Result<usize, EnttecProError> {
+    if data.len() > MAX_DATA_LENGTH {
+        return Err(EnttecProError::LengthOutOfRange);
+    }
+
+    let message_size = data.len() + MIN_PACKAGE_SIZE;
+    let message = buffer
+        .get_mut(..message_size)
+        .ok_or(EnttecProError::InvalidArgument)?;
+
+    message[0] = START_OF_MESSAGE_DELIMITER;
+    message[1] = label;
+    message[2..4].copy_from_slice(&(data.len() as u16).to_le_bytes());
+    message[4..message_size - 1].copy_from_slice(data);
+    message[message_size - 1] = END_OF_MESSAGE_DELIMITER;
+
+    Ok(message_size)
+}
+
+/// A message of the Enttec DMX Pro serial protocol.
+/// Refer to the api documentation for the available labels.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EnttecMessage {
+    /// The label that identifies the message type.
     pub label: u8,
-    pub data: Vec<u8>,
+    /// The payload of the message. It can't be bigger than 600 bytes.
+    pub data: MessageData,
 }
 
 impl EnttecMessage {
-    pub fn serialize(&self) -> Vec<u8> {
-        assert!(self.data.len() <= MAX_DATA_LENGTH);
+    /// Serializes the message including the delimiters.
+    ///
+    /// Panics if the payload is bigger than 600 bytes.
+    pub fn serialize(&self) -> SerializedMessage {
+        let mut buffer = [0u8; MAX_DATA_LENGTH + MIN_PACKAGE_SIZE];
+        let message_size = self
+            .serialize_into(&mut buffer)
+            .expect("payload is bigger than 600 bytes");
 
-        let mut result = Vec::new();
-        result.push(START_OF_MESSAGE_DELIMITER);
-        result.push(self.label);
-        result.extend_from_slice(&(self.data.len() as u16).to_le_bytes());
-        result.extend_from_slice(&self.data);
-        result.push(END_OF_MESSAGE_DELIMITER);
+        #[cfg(feature = "heapless")]
+        {
+            SerializedMessage::from_slice(&buffer[..message_size]).unwrap()
+        }
+        #[cfg(not(feature = "heapless"))]
+        {
+            buffer[..message_size].to_vec()
+        }
+    }
 
-        result
+    /// Serializes the message including the delimiters into `buffer` and returns the size of
+    /// the serialized message. A buffer of 605 bytes fits every message.
+    ///
+    /// Returns [EnttecProError::LengthOutOfRange] if the payload is bigger than 600 bytes and
+    /// [EnttecProError::InvalidArgument] if the buffer is too small. The buffer is left
+    /// unchanged in both cases.
+    pub fn serialize_into(&self, buffer: &mut [u8]) -> Result<usize, EnttecProError> {
+        serialize_message_into(self.label, &self.data, buffer)
     }
 
+    /// Deserializes a message including the delimiters.
+    /// Returns `None` if the message is malformed.
     pub fn deserialize(data: &[u8]) -> Option<Self> {
         if data.len() < MIN_PACKAGE_SIZE {
             return None;
@@ -70,21 +174,95 @@ impl EnttecMessage {
         }
 
         let label = data[1];
-        let data_length = u16::from_le_bytes(data[2..4].try_into().unwrap()) as usize;
+        let data_length = u16::from_le_bytes([data[2], data[3]]) as usize;
         if data_length > MAX_DATA_LENGTH || data.len() != MIN_PACKAGE_SIZE + data_length {
             return None;
         }
 
-        let mut enttec_data = Vec::new();
-        enttec_data.extend_from_slice(&data[4..data_length + 4]);
-
         Some(Self {
             label,
-            data: enttec_data,
+            data: to_message_data(&data[4..data_length + 4]),
+        })
+    }
+
+    /// Returns the status byte of a received dmx packet (label 5) or `None` if the message
+    /// is empty.
+    pub fn receive_status(&self) -> Option<ReceiveStatus> {
+        self.data
+            .first()
+            .copied()
+            .map(ReceiveStatus::from_status_byte)
+    }
+
+    /// Returns the package of a received dmx packet (label 5) without the status byte. It starts
+    /// with the start code, except for discovery responses, and is empty if the message doesn't
+    /// contain a package.
+    pub fn payload(&self) -> &[u8] {
+        self.data.get(1..).unwrap_or_default()
+    }
+
+    /// Returns the start code of a received dmx packet (label 5) or `None` if the message
+    /// doesn't contain a package.
+    pub fn start_code(&self) -> Option<u8> {
+        self.payload().first().copied()
+    }
+
+    /// Decodes a change of state packet (label 9) into the slots that changed. Returns `None`
+    /// if the message isn't a change of state packet or the amount of values doesn't match
+    /// the changed bit array.
+    pub fn changed_channels(&self) -> Option<ChangedChannels<'_>> {
+        if self.label != RECEIVED_DMX_CHANGE_OF_STATE_PACKET
+            || self.data.len() < CHANGE_OF_STATE_HEADER_SIZE
+        {
+            return None;
+        }
+
+        let changed_bits = &self.data[1..CHANGE_OF_STATE_HEADER_SIZE];
+        let changed_values = &self.data[CHANGE_OF_STATE_HEADER_SIZE..];
+        let changed_count: u32 = changed_bits.iter().map(|byte| byte.count_ones()).sum();
+        if changed_count as usize != changed_values.len() {
+            return None;
+        }
+
+        Some(ChangedChannels {
+            // The start changed byte number is counted in blocks of 8 slots.
+            first_slot: self.data[0] as u16 * 8,
+            changed_bits,
+            changed_values: changed_values.iter(),
+            bit: 0,
         })
     }
 }
 
+/// Iterator over the slots that changed, returned by [EnttecMessage::changed_channels].
+#[derive(Debug, Clone)]
+pub struct ChangedChannels<'a> {
+    first_slot: u16,
+    changed_bits: &'a [u8],
+    changed_values: std::slice::Iter<'a, u8>,
+    bit: usize,
+}
+
+impl Iterator for ChangedChannels<'_> {
+    type Item = SlotChange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bit < self.changed_bits.len() * 8 {
+            let bit = self.bit;
+            self.bit += 1;
+
+            if self.changed_bits[bit / 8] & (1 << (bit % 8)) != 0 {
+                return Some(SlotChange {
+                    slot: self.first_slot + bit as u16,
+                    value: *self.changed_values.next()?,
+                });
+            }
+        }
+
+        None
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum EnttecProError {
     /// The data size in the received package is too big.
@@ -95,6 +273,18 @@ pub enum EnttecProError {
     RdmDeserializationError(RdmDeserializationError),
     /// An error was raised by the ftdi library.
     FtdiError(TimeoutError),
+    /// The responder didn't acknowledge the rdm request.
+    NotAcknowledged(NackReason),
+    /// The responder acknowledged the rdm request but the result isn't ready yet.
+    /// The value is the estimated time in 100ms steps.
+    NotReady(u16),
+    /// The rdm response doesn't match the request or its parameter data couldn't been deserialized.
+    InvalidResponse,
+    /// An argument passed to the driver is out of range.
+    InvalidArgument,
+    /// The widget reported an error while receiving the response, so the response is
+    /// incomplete.
+    WidgetReported(ReceiveStatus),
 }
 
 impl From<TimeoutError> for EnttecProError {
@@ -118,6 +308,21 @@ impl Display for EnttecProError {
                 return write!(f, "{}", rdm_deserialization_error);
             }
             EnttecProError::FtdiError(ftdi_error) => return write!(f, "{}", ftdi_error),
+            EnttecProError::NotAcknowledged(nack_reason) => {
+                return write!(f, "request not acknowledged ({:?})", nack_reason);
+            }
+            EnttecProError::NotReady(estimated_time) => {
+                return write!(
+                    f,
+                    "response not ready (retry in {}ms)",
+                    *estimated_time as u32 * 100
+                );
+            }
+            EnttecProError::InvalidResponse => "invalid rdm response",
+            EnttecProError::InvalidArgument => "argument out of range",
+            EnttecProError::WidgetReported(receive_status) => {
+                return write!(f, "widget reported a receive error ({:?})", receive_status);
+            }
         };
 
         write!(f, "{}", text_to_write)
@@ -126,41 +331,641 @@ impl Display for EnttecProError {
 
 impl Error for EnttecProError {}
 
+/// The status byte the widget sends in front of every received package.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ReceiveStatus {
+    /// The receive queue of the widget overflowed.
+    pub queue_overflow: bool,
+    /// The uart of the widget reported an overrun.
+    pub overrun: bool,
+}
+
+impl ReceiveStatus {
+    fn from_status_byte(status_byte: u8) -> Self {
+        Self {
+            queue_overflow: status_byte & 0x01 != 0,
+            overrun: status_byte & 0x02 != 0,
+        }
+    }
+
+    /// Returns `true` if the package was received without errors.
+    pub fn is_ok(&self) -> bool {
+        !self.queue_overflow && !self.overrun
+    }
+}
+
+/// A slot that changed, received by [EnttecProDriver::receive_dmx_changes].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SlotChange {
+    /// The index of the slot. Slot 0 is the start code.
+    pub slot: u16,
+    /// The new value of the slot.
+    pub value: u8,
+}
+
+/// The parameters of the widget, received by [EnttecProDriver::get_widget_parameters].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct WidgetParameters {
+    /// The firmware version of the widget.
+    pub firmware_version: u16,
+    /// The length of the transmitted break in 10.67µs steps.
+    pub break_time: u8,
+    /// The length of the transmitted MAB in 10.67µs steps.
+    pub mab_time: u8,
+    /// The amount of frames the widget transmits per second. `0` means as fast as possible.
+    pub output_rate: u8,
+}
+
+/// How long the driver waits for the response to an rdm request. Set using
+/// [EnttecProDriver::set_rdm_response_timeouts].
+///
+/// The first attempt waits for `initial`. Every retry of [EnttecProDriver::send_rdm_with_retry]
+/// doubles the wait up to `max`, so fast responders are handled quickly while slow ones still
+/// get a chance to answer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RdmResponseTimeouts {
+    /// The wait for the first attempt. Defaults to 20ms, which leaves room for the usb latency
+    /// on top of the response time of the device.
+    pub initial: Duration,
+    /// The longest wait for a retry. Defaults to 50ms.
+    pub max: Duration,
+}
+
+impl Default for RdmResponseTimeouts {
+    fn default() -> Self {
+        Self {
+            initial: DEFAULT_RDM_RESPONSE_TIMEOUT,
+            max: DEFAULT_SERIAL_TIMEOUT,
+        }
+    }
+}
+
+/// A response received by [EnttecProDriver::send_rdm_with_retry].
+#[derive(Debug)]
+pub struct RetriedRdmResponse {
+    /// The received rdm package. This is `None` for broadcast requests, since they
+    /// aren't answered.
+    pub response: Option<RdmData>,
+    /// The amount of times the request has been sent until a response was received.
+    pub attempts: u16,
+}
+
 pub struct EnttecProDriver {
     serial_port: Ftdi,
+    rdm_uid: Option<UniqueIdentifier>,
+    transaction_number: u8,
+    last_discovery_status: Option<ReceiveStatus>,
+    blackout_on_drop: bool,
+    rdm_response_timeouts: RdmResponseTimeouts,
+    output_paused: bool,
+    rdm_pause_end: Option<Instant>,
 }
 
 impl EnttecProDriver {
     pub fn new(mut serial_port: Ftdi) -> Result<Self, EnttecProError> {
-        serial_port.set_timeouts(Duration::from_millis(50), Duration::from_millis(50))?;
+        serial_port.set_timeouts(DEFAULT_SERIAL_TIMEOUT, DEFAULT_SERIAL_TIMEOUT)?;
 
-        Ok(Self { serial_port })
+        Ok(Self {
+            serial_port,
+            rdm_uid: None,
+            transaction_number: 0,
+            last_discovery_status: None,
+            blackout_on_drop: false,
+            rdm_response_timeouts: RdmResponseTimeouts::default(),
+            output_paused: false,
+            rdm_pause_end: None,
+        })
     }
 
+    /// Requests the serial number of the widget and derives the rdm uid from it.
+    ///
+    /// Gives up after 1s if the widget doesn't answer, e.g. because it doesn't run the rdm
+    /// firmware or isn't an Enttec DMX Pro at all.
     pub fn get_rdm_uid(&mut self) -> Result<UniqueIdentifier, EnttecProError> {
-        self.serial_port.write_all(
-            &EnttecMessage {
-                label: GET_WIDGET_SERIAL_NUMBER,
-                data: Vec::new(),
-            }
-            .serialize(),
+        self.get_rdm_uid_with_timeout(WIDGET_RESPONSE_TIMEOUT)
+    }
+
+    /// Same as [EnttecProDriver::get_rdm_uid] but gives up after `timeout`.
+    pub fn get_rdm_uid_with_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<UniqueIdentifier, EnttecProError> {
+        self.write_message(GET_WIDGET_SERIAL_NUMBER, &[])?;
+
+        let response =
+            self.read_package_with_label(GET_WIDGET_SERIAL_NUMBER, Instant::now() + timeout)?;
+
+        let device_address = u32::from_le_bytes(
+            response.data[..]
+                .try_into()
+                .or(Err(EnttecProError::LengthOutOfRange))?,
+        );
+        // A widget reporting 0xFFFFFFFF as its serial number would end up with a broadcast uid.
+        let rdm_uid = UniqueIdentifier::new(ENTTEC_MANUFACTURER_ID, device_address)
+            .or(Err(EnttecProError::InvalidResponse))?;
+        self.rdm_uid = Some(rdm_uid);
+
+        Ok(rdm_uid)
+    }
+
+    /// Requests the parameters of the widget.
+    ///
+    /// The widget doesn't output faster than [WidgetParameters::output_rate], so passing it to
+    /// [dmx_rdm_utils::rate_limit::RateLimited::with_max_rate] prevents frames from piling up.
+    pub fn get_widget_parameters(&mut self) -> Result<WidgetParameters, EnttecProError> {
+        // The request contains the size of the requested user configuration, which isn't used.
+        self.write_message(GET_WIDGET_PARAMETERS, &[0, 0])?;
+
+        let response = self.read_package_with_label(
+            GET_WIDGET_PARAMETERS,
+            Instant::now() + WIDGET_RESPONSE_TIMEOUT,
         )?;
 
-        let response = loop {
-            let response = self.read_package()?;
+        if response.data.len() < 5 {
+            return Err(EnttecProError::LengthOutOfRange);
+        }
+
+        Ok(WidgetParameters {
+            firmware_version: u16::from_le_bytes([response.data[0], response.data[1]]),
+            break_time: response.data[2],
+            mab_time: response.data[3],
+            output_rate: response.data[4],
+        })
+    }
+
+    /// Restores the default state of the widget. Receive on change is turned off and the
+    /// break time, MAB time and output rate are set to their factory defaults (96µs, 10.67µs
+    /// and 40 frames per second).
+    ///
+    /// The firmware doesn't offer a way to reboot the widget, so this doesn't recover a widget
+    /// that stopped responding.
+    pub fn reset_widget(&mut self) -> Result<(), EnttecProError> {
+        const DEFAULT_BREAK_TIME: u8 = 9;
+        const DEFAULT_MAB_TIME: u8 = 1;
+        const DEFAULT_OUTPUT_RATE: u8 = 40;
+
+        self.set_receive_on_change(false)?;
+
+        // The user configuration isn't changed, so its size is zero.
+        self.send_raw_message(
+            SET_WIDGET_PARAMETERS,
+            &[
+                0,
+                0,
+                DEFAULT_BREAK_TIME,
+                DEFAULT_MAB_TIME,
+                DEFAULT_OUTPUT_RATE,
+            ],
+        )
+    }
+
+    /// Sends a message with a custom label. This allows using features of the widget that aren't
+    /// supported by this driver.
+    pub fn send_raw_message(&mut self, label: u8, data: &[u8]) -> Result<(), EnttecProError> {
+        if data.len() > MAX_DATA_LENGTH {
+            return Err(EnttecProError::LengthOutOfRange);
+        }
 
-            if response.label == GET_WIDGET_SERIAL_NUMBER {
-                break response;
+        self.write_message(label, data)
+    }
+
+    /// Receives the next message the widget sends, regardless of its label.
+    pub fn receive_raw_message(&mut self) -> Result<EnttecMessage, EnttecProError> {
+        self.read_package()
+    }
+
+    /// Lets the widget only send the slots that changed instead of every received frame.
+    /// Use [EnttecProDriver::receive_dmx_changes] to receive the changes.
+    pub fn set_receive_on_change(&mut self, enabled: bool) -> Result<(), EnttecProError> {
+        self.send_raw_message(RECEIVE_DMX_ON_CHANGE, &[enabled as u8])
+    }
+
+    /// Receives the next change of state packet. Requires the receive on change mode to be
+    /// enabled using [EnttecProDriver::set_receive_on_change].
+    ///
+    /// A single packet covers up to 40 consecutive slots, so bigger changes arrive in
+    /// multiple packets.
+    pub fn receive_dmx_changes(&mut self) -> Result<Vec<SlotChange>, EnttecProError> {
+        let package = loop {
+            let recv_package = self.read_package()?;
+            if recv_package.label == RECEIVED_DMX_CHANGE_OF_STATE_PACKET {
+                break recv_package;
             }
         };
 
-        if response.data.len() != 4 {
+        if package.data.len() < CHANGE_OF_STATE_HEADER_SIZE {
             return Err(EnttecProError::LengthOutOfRange);
         }
 
-        let device_address = u32::from_le_bytes(response.data.try_into().unwrap());
+        Ok(package
+            .changed_channels()
+            .ok_or(EnttecProError::EnttecDeserializationError)?
+            .collect())
+    }
 
-        Ok(UniqueIdentifier::new(ENTTEC_MANUFACTURER_ID, device_address).unwrap())
+    /// Returns the status of the last received discovery response or `None` if no device
+    /// responded.
+    ///
+    /// If the discovery returned a collision but the status isn't ok, the response was probably
+    /// corrupted by the widget instead of multiple devices answering at once.
+    pub fn last_discovery_status(&self) -> Option<ReceiveStatus> {
+        self.last_discovery_status
+    }
+
+    /// Sets how long the driver waits for rdm responses. A `max` that is shorter than
+    /// `initial` is raised to `initial`.
+    pub fn set_rdm_response_timeouts(&mut self, timeouts: RdmResponseTimeouts) {
+        self.rdm_response_timeouts = RdmResponseTimeouts {
+            initial: timeouts.initial,
+            max: timeouts.max.max(timeouts.initial),
+        };
+    }
+
+    /// Sends an rdm request and receives the response. If no response is received in time
+    /// the request is resent up to `retries` times, waiting longer for each retry as
+    /// configured by [EnttecProDriver::set_rdm_response_timeouts].
+    ///
+    /// Broadcast requests are sent once without waiting for a response.
+    ///
+    /// <div class="warning">Only use this for requests that are safe to repeat like GET requests.</div>
+    pub fn send_rdm_with_retry(
+        &mut self,
+        package: RdmData,
+        retries: u8,
+    ) -> Result<RetriedRdmResponse, EnttecProError> {
+        let is_broadcast = matches!(
+            package,
+            RdmData::Request(ref request) if request.destination_uid.is_broadcast()
+        );
+        let mut attempts = 0;
+        let mut response_timeout = self.rdm_response_timeouts.initial;
+
+        loop {
+            attempts += 1;
+            self.write_rdm(&package)?;
+
+            if is_broadcast {
+                return Ok(RetriedRdmResponse {
+                    response: None,
+                    attempts,
+                });
+            }
+
+            match self.read_rdm_with_timeout(response_timeout) {
+                Ok(response) => {
+                    return Ok(RetriedRdmResponse {
+                        response: Some(response),
+                        attempts,
+                    })
+                }
+                Err(EnttecProError::FtdiError(TimeoutError::Timeout { .. }))
+                    if attempts <= retries as u16 =>
+                {
+                    response_timeout = (response_timeout * 2).min(self.rdm_response_timeouts.max);
+                    sleep(RDM_RETRY_DELAY);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Sends the rdm requests one after another with as little delay as possible and returns
+    /// a result for each of them.
+    ///
+    /// Rdm is half duplex, so there is only ever one request in flight. The results are in
+    /// the same order as the requests and the response of a request is only taken if its
+    /// transaction number matches, so late responses of timed out requests are dropped.
+    /// Broadcast requests don't get a response and result in `Ok(None)`. Every response is
+    /// waited for as long as the initial rdm response timeout, requests aren't retried.
+    ///
+    /// An error only affects the result of its own request, the remaining requests are
+    /// still sent.
+    pub fn send_many(
+        &mut self,
+        requests: &[RdmData],
+    ) -> Vec<Result<Option<RdmData>, EnttecProError>> {
+        requests
+            .iter()
+            .map(|package| self.transact_no_delay(package))
+            .collect()
+    }
+
+    fn transact_no_delay(&mut self, package: &RdmData) -> Result<Option<RdmData>, EnttecProError> {
+        let request = match package {
+            RdmData::Request(request) => request,
+            RdmData::Response(_) => return Err(EnttecProError::InvalidArgument),
+        };
+
+        if request.destination_uid.is_broadcast() {
+            // Broadcasts don't have a response to wait for, the pause gives the responders
+            // time to process them.
+            self.write_rdm(package)?;
+            return Ok(None);
+        }
+
+        self.write_rdm_no_delay(package)?;
+
+        Ok(Some(self.receive_rdm_matching(request.transaction_number)?))
+    }
+
+    /// Sends an rdm request and waits up to `timeout` for the response with the same
+    /// transaction number. Responses to other requests are discarded.
+    ///
+    /// Broadcast requests aren't answered, so they return [EnttecProError::InvalidArgument].
+    pub fn transact_rdm(
+        &mut self,
+        request: RdmData,
+        timeout: Duration,
+    ) -> Result<RdmData, EnttecProError> {
+        let transaction_number = match request {
+            RdmData::Request(ref request) if !request.destination_uid.is_broadcast() => {
+                request.transaction_number
+            }
+            _ => return Err(EnttecProError::InvalidArgument),
+        };
+
+        let deadline = Instant::now() + timeout;
+        let result = self
+            .write_rdm_no_delay(&request)
+            .and_then(|_| self.read_rdm_until(transaction_number, deadline));
+
+        self.serial_port
+            .set_timeouts(DEFAULT_SERIAL_TIMEOUT, DEFAULT_SERIAL_TIMEOUT)?;
+
+        result
+    }
+
+    /// Receives the rdm response with the given transaction number. Responses to other requests
+    /// (e.g. late responses of timed out requests) are discarded.
+    ///
+    /// Returns a timeout error if no matching response arrives within the initial rdm response
+    /// timeout (see [EnttecProDriver::set_rdm_response_timeouts]).
+    pub fn receive_rdm_matching(
+        &mut self,
+        transaction_number: u8,
+    ) -> Result<RdmData, EnttecProError> {
+        let deadline = Instant::now() + self.rdm_response_timeouts.initial;
+        let result = self.read_rdm_until(transaction_number, deadline);
+
+        self.serial_port
+            .set_timeouts(DEFAULT_SERIAL_TIMEOUT, DEFAULT_SERIAL_TIMEOUT)?;
+
+        result
+    }
+
+    /// Reads messages until one with the given label arrives, discarding all others. Gives up
+    /// once the `deadline` passed, so a widget that keeps sending other messages can't stall
+    /// the caller.
+    fn read_package_with_label(
+        &mut self,
+        label: u8,
+        deadline: Instant,
+    ) -> Result<EnttecMessage, EnttecProError> {
+        let result = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break Err(EnttecProError::FtdiError(TimeoutError::Timeout {
+                    actual: 0,
+                    expected: 1,
+                }));
+            }
+
+            if let Err(error) = self
+                .serial_port
+                .set_timeouts(remaining, DEFAULT_SERIAL_TIMEOUT)
+            {
+                break Err(error.into());
+            }
+
+            match self.read_package() {
+                Ok(response) if response.label == label => break Ok(response),
+                Ok(_) => {}
+                Err(error) => break Err(error),
+            }
+        };
+
+        self.serial_port
+            .set_timeouts(DEFAULT_SERIAL_TIMEOUT, DEFAULT_SERIAL_TIMEOUT)?;
+
+        result
+    }
+
+    fn read_rdm_until(
+        &mut self,
+        transaction_number: u8,
+        deadline: Instant,
+    ) -> Result<RdmData, EnttecProError> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(EnttecProError::FtdiError(TimeoutError::Timeout {
+                    actual: 0,
+                    expected: 1,
+                }));
+            }
+
+            self.serial_port
+                .set_timeouts(remaining, DEFAULT_SERIAL_TIMEOUT)?;
+
+            let response = self.read_rdm()?;
+            if let RdmData::Response(ref response_data) = response {
+                if response_data.transaction_number == transaction_number {
+                    return Ok(response);
+                }
+            }
+        }
+    }
+
+    /// Sends an ascii text packet (start code `0x17`) for the given page.
+    ///
+    /// The page byte and the text have to fit into 512 slots, so the text can't be longer than
+    /// 511 characters. The text must only contain ascii characters.
+    pub fn send_text_packet(&mut self, page: u8, text: &str) -> Result<(), EnttecProError> {
+        if !text.is_ascii() {
+            return Err(EnttecProError::InvalidArgument);
+        }
+
+        let mut package = Vec::with_capacity(text.len() + 1);
+        package.push(page);
+        package.extend_from_slice(text.as_bytes());
+
+        self.write_custom_package(StartCode::Text, &package)
+    }
+
+    /// Sends a full frame with all 512 channels set to zero.
+    ///
+    /// The widget keeps repeating the last frame it received, so the output stays dark until
+    /// the next frame is sent. This is sent even while the output is
+    /// [paused](EnttecProDriver::pause_output), which keeps the output dark until it's resumed.
+    pub fn blackout(&mut self) -> Result<(), EnttecProError> {
+        self.send_frame(StartCode::Null, &[0; DMX_MAX_PACKAGE_SIZE - 1])
+    }
+
+    /// Holds the output at the last frame that was sent, for example while reconfiguring.
+    ///
+    /// The firmware has no label for stopping its output, so this is emulated by the driver:
+    /// while paused, dmx and custom start code packages are validated but not sent to the
+    /// widget. The widget keeps repeating the last frame it received, so the lights **freeze**
+    /// in their last state instead of going dark. Call [EnttecProDriver::blackout] to go dark
+    /// while paused. Rdm requests are still sent.
+    pub fn pause_output(&mut self) {
+        self.output_paused = true;
+    }
+
+    /// Ends a [pause](EnttecProDriver::pause_output). The widget keeps holding the last frame
+    /// sent before or during the pause until the next package is sent.
+    pub fn resume_output(&mut self) {
+        self.output_paused = false;
+    }
+
+    /// Returns `true` if the output is [paused](EnttecProDriver::pause_output).
+    pub fn is_output_paused(&self) -> bool {
+        self.output_paused
+    }
+
+    /// Sends a [blackout](EnttecProDriver::blackout) when the driver is dropped. This
+    /// includes dropping it while unwinding from a panic, so the lights don't freeze in
+    /// their last state.
+    pub fn set_blackout_on_drop(&mut self, enabled: bool) {
+        self.blackout_on_drop = enabled;
+    }
+
+    /// Sends every slice as its own dmx package (without the start code).
+    ///
+    /// The widget keeps repeating the last package it received, so every package is held for
+    /// one output period of the widget (25ms at the default rate) before the next one is sent.
+    pub fn send_multiframe(&mut self, frames: &[&[u8]]) -> Result<(), EnttecProError> {
+        for (index, frame) in frames.iter().enumerate() {
+            if index != 0 {
+                sleep(WIDGET_FRAME_PERIOD);
+            }
+
+            self.write_custom_package(StartCode::Null, frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the already assembled `frame`, which has to start with the start code.
+    ///
+    /// Prefer `send_dmx_package` and `send_custom_package`, which take the slots and the start
+    /// code separately. Like them this refuses rdm packages.
+    pub fn write_raw_frame(&mut self, frame: &[u8]) -> Result<(), EnttecProError> {
+        match frame.split_first() {
+            Some((&start_code, package)) => self.write_custom_package(start_code.into(), package),
+            None => Err(EnttecProError::InvalidArgument),
+        }
+    }
+
+    fn write_custom_package(
+        &mut self,
+        start_code: StartCode,
+        package: &[u8],
+    ) -> Result<(), EnttecProError> {
+        validate_dmx_frame(start_code, package).map_err(|error| match error {
+            FrameError::TooLong => EnttecProError::LengthOutOfRange,
+            FrameError::Empty | FrameError::RdmStartCode => EnttecProError::InvalidArgument,
+        })?;
+
+        if self.output_paused {
+            return Ok(());
+        }
+
+        self.send_frame(start_code, package)
+    }
+
+    fn send_frame(&mut self, start_code: StartCode, package: &[u8]) -> Result<(), EnttecProError> {
+        let mut frame = [0u8; DMX_MAX_PACKAGE_SIZE];
+        frame[0] = start_code.into();
+        frame[1..=package.len()].copy_from_slice(package);
+
+        self.write_message(SEND_DMX_PACKET_REQUEST, &frame[..=package.len()])
+    }
+
+    /// Sends an rdm package without the short pause the driver usually makes before sending
+    /// the next package.
+    ///
+    /// <div class="warning">Some widgets need the pause and drop packages that follow too
+    /// quickly. Only use this if your widget is known to work without it.</div>
+    pub fn send_rdm_no_delay(&mut self, package: RdmData) -> Result<(), EnttecProError> {
+        self.write_rdm_no_delay(&package)
+    }
+
+    /// Sends an rdm package and makes the next package wait for the pause some widgets need.
+    /// The pause runs while waiting for the response, so it only delays anything if the
+    /// response arrives sooner or no response is read at all.
+    fn write_rdm(&mut self, package: &RdmData) -> Result<(), EnttecProError> {
+        self.write_rdm_no_delay(package)?;
+        self.rdm_pause_end = Some(Instant::now() + RDM_SEND_PAUSE);
+
+        Ok(())
+    }
+
+    fn write_rdm_no_delay(&mut self, package: &RdmData) -> Result<(), EnttecProError> {
+        // Only DISC_UNIQUE_BRANCH responses are sent without a break, which is what the discovery
+        // label is for. The DISC_MUTE and DISC_UN_MUTE responses are regular rdm packages.
+        let label = match package {
+            RdmData::Request(request)
+                if request.command_class == RequestCommandClass::DiscoveryCommand
+                    && request.parameter_id == 0x0001 =>
+            {
+                SEND_RDM_DISCOVERY_REQUEST
+            }
+            _ => SEND_RDM_PACKET_REQUEST,
+        };
+
+        self.write_message(label, &package.serialize())
+    }
+
+    /// Writes a message without allocating, which keeps the send paths cheap for high refresh
+    /// rates. The payload can't be bigger than 600 bytes.
+    fn write_message(&mut self, label: u8, data: &[u8]) -> Result<(), EnttecProError> {
+        if let Some(rdm_pause_end) = self.rdm_pause_end.take() {
+            sleep(rdm_pause_end.saturating_duration_since(Instant::now()));
+        }
+
+        let mut buffer = [0u8; MAX_DATA_LENGTH + MIN_PACKAGE_SIZE];
+        let message_size = serialize_message_into(label, data, &mut buffer)?;
+
+        self.serial_port.write_all(&buffer[..message_size])?;
+
+        Ok(())
+    }
+
+    /// Reads an rdm package using `timeout` as the serial read timeout.
+    fn read_rdm_with_timeout(&mut self, timeout: Duration) -> Result<RdmData, EnttecProError> {
+        // Changing the timeouts takes a usb round trip, so the default is kept whenever possible.
+        if timeout == DEFAULT_SERIAL_TIMEOUT {
+            return self.read_rdm();
+        }
+
+        self.serial_port
+            .set_timeouts(timeout, DEFAULT_SERIAL_TIMEOUT)?;
+        let result = self.read_rdm();
+        self.serial_port
+            .set_timeouts(DEFAULT_SERIAL_TIMEOUT, DEFAULT_SERIAL_TIMEOUT)?;
+
+        result
+    }
+
+    fn read_rdm(&mut self) -> Result<RdmData, EnttecProError> {
+        let package = loop {
+            let recv_package = self.read_package()?;
+            if recv_package.label == RECEIVED_DMX_PACKET {
+                break recv_package;
+            }
+        };
+
+        if package.start_code().is_none() {
+            return Err(EnttecProError::LengthOutOfRange);
+        }
+
+        // The package is cut short, so it's more useful to report why than to fail parsing it.
+        if let Some(receive_status) = package.receive_status().filter(|status| !status.is_ok()) {
+            return Err(EnttecProError::WidgetReported(receive_status));
+        }
+
+        RdmData::deserialize(package.payload()).map_err(EnttecProError::RdmDeserializationError)
     }
 
     fn read_package(&mut self) -> Result<EnttecMessage, EnttecProError> {
@@ -174,7 +979,7 @@ impl EnttecProDriver {
         }
 
         self.serial_port.read_all(&mut receive_buffer[1..4])?;
-        let data_size = u16::from_le_bytes(receive_buffer[2..4].try_into().unwrap()) as usize;
+        let data_size = u16::from_le_bytes([receive_buffer[2], receive_buffer[3]]) as usize;
 
         if data_size > MAX_DATA_LENGTH {
             return Err(EnttecProError::LengthOutOfRange);
@@ -192,6 +997,15 @@ impl EnttecProDriver {
     }
 }
 
+impl Drop for EnttecProDriver {
+    fn drop(&mut self) {
+        if self.blackout_on_drop {
+            // There is no way to report the error anymore.
+            self.blackout().ok();
+        }
+    }
+}
+
 impl ControllerDriverErrorDef for EnttecProDriver {
     type DriverError = EnttecProError;
 }
@@ -202,73 +1016,30 @@ impl CustomStartCodeControllerDriver for EnttecProDriver {
         start_code: u8,
         package: &[u8],
     ) -> Result<(), DmxError<Self::DriverError>> {
-        let mut data = Vec::new();
-
-        data.push(start_code);
-        data.extend_from_slice(package);
-
-        self.serial_port
-            .write(
-                &EnttecMessage {
-                    label: SEND_DMX_PACKET_REQUEST,
-                    data,
-                }
-                .serialize(),
-            )
-            .map_err(|error| DmxError::DriverError(error.into()))?;
-
-        Ok(())
+        Ok(self.write_custom_package(start_code.into(), package)?)
     }
 }
 
 impl DmxControllerDriver for EnttecProDriver {
     fn send_dmx_package(&mut self, package: &[u8]) -> Result<(), DmxError<Self::DriverError>> {
-        self.send_custom_package(DMX_NULL_START, package)
+        Ok(self.write_custom_package(StartCode::Null, package)?)
     }
 }
 
 impl RdmControllerDriver for EnttecProDriver {
     fn send_rdm(&mut self, package: RdmData) -> Result<(), DmxError<Self::DriverError>> {
-        let label = if let RdmData::Request(ref request) = package {
-            if request.parameter_id == 0x0001 {
-                SEND_RDM_DISCOVERY_REQUEST
-            } else {
-                SEND_RDM_PACKET_REQUEST
-            }
-        } else {
-            SEND_RDM_PACKET_REQUEST
-        };
-
-        self.serial_port
-            .write(
-                &EnttecMessage {
-                    label,
-                    data: package.serialize().to_vec(),
-                }
-                .serialize(),
-            )
-            .map_err(|error| DmxError::DriverError(error.into()))?;
-
-        sleep(Duration::from_millis(5));
-
-        Ok(())
+        Ok(self.write_rdm(&package)?)
     }
 
     fn receive_rdm(&mut self) -> Result<RdmData, DmxError<Self::DriverError>> {
-        let package = loop {
-            let recv_package = self.read_package()?;
-            if recv_package.label == RECEIVED_DMX_PACKET {
-                break recv_package;
-            }
-        };
-
-        Ok(RdmData::deserialize(&package.data[1..])
-            .map_err(EnttecProError::RdmDeserializationError)?)
+        Ok(self.read_rdm_with_timeout(self.rdm_response_timeouts.initial)?)
     }
 
     fn receive_rdm_discovery_response(
         &mut self,
     ) -> Result<DiscoveryOption, DmxError<Self::DriverError>> {
+        self.last_discovery_status = None;
+
         let package = loop {
             let recv_package = match self.read_package() {
                 Ok(recv_package) => recv_package,
@@ -283,9 +1054,19 @@ impl RdmControllerDriver for EnttecProDriver {
             }
         };
 
-        let discovery_option = match deserialize_discovery_response(&package.data[1..]) {
-            Ok(unique_identifier) => DiscoveryOption::Found(unique_identifier),
-            Err(_) => DiscoveryOption::Collision,
+        // Discovery responses don't have a start code.
+        if package.payload().is_empty() {
+            return Err(EnttecProError::LengthOutOfRange.into());
+        }
+
+        self.last_discovery_status = package.receive_status();
+
+        let discovery_option = match deserialize_discovery_response(package.payload()) {
+            // A uid that can't belong to a device was most likely decoded from line noise.
+            Ok(unique_identifier) if is_plausible_uid(&unique_identifier) => {
+                DiscoveryOption::Found(unique_identifier)
+            }
+            _ => DiscoveryOption::Collision,
         };
 
         Ok(discovery_option)
@@ -308,3 +1089,220 @@ pub fn create_dmx_controller_from_enttec_pro(
 
     Ok(DmxController::new(driver, &DmxControllerConfig { rdm_uid }))
 }
+
+/// Same as [create_dmx_controller_from_enttec_pro] but retries the serial number handshake up
+/// to `retries` times. Right after plugging in the widget the first requests sometimes get lost.
+pub fn create_dmx_controller_from_enttec_pro_with_retries(
+    serial_port: Ftdi,
+    retries: u8,
+) -> Result<DmxController<EnttecProDriver>, EnttecProError> {
+    let mut driver = EnttecProDriver::new(serial_port)?;
+
+    let mut attempts: u16 = 0;
+    let rdm_uid = loop {
+        attempts += 1;
+
+        match driver.get_rdm_uid() {
+            Ok(rdm_uid) => break rdm_uid,
+            Err(_) if attempts <= retries as u16 => sleep(HANDSHAKE_RETRY_DELAY),
+            Err(error) => return Err(error),
+        }
+    };
+
+    Ok(DmxController::new(driver, &DmxControllerConfig { rdm_uid }))
+}
+
+/// Same as [create_dmx_controller_from_enttec_pro] but skips requesting the serial number of
+/// the widget. Use this for widgets that don't run the rdm firmware and are only used for dmx.
+///
+/// The controller uses `rdm_uid` as its uid or a placeholder with the Enttec manufacturer id if
+/// it is `None`.
+pub fn create_dmx_controller_from_enttec_pro_dmx_only(
+    serial_port: Ftdi,
+    rdm_uid: Option<UniqueIdentifier>,
+) -> Result<DmxController<EnttecProDriver>, EnttecProError> {
+    let driver = EnttecProDriver::new(serial_port)?;
+    let rdm_uid = match rdm_uid {
+        Some(rdm_uid) => rdm_uid,
+        None => PLACEHOLDER_RDM_UID,
+    };
+
+    Ok(DmxController::new(driver, &DmxControllerConfig { rdm_uid }))
+}
+
+/// Same as [create_dmx_controller_from_enttec_pro] but uses the provided `timeout` for the
+/// serial number handshake. This helps with slow widgets or busy usb hubs.
+///
+/// After the handshake the default timeout is restored.
+pub fn create_dmx_controller_from_enttec_pro_with_timeout(
+    serial_port: Ftdi,
+    timeout: Duration,
+) -> Result<DmxController<EnttecProDriver>, EnttecProError> {
+    let mut driver = EnttecProDriver::new(serial_port)?;
+
+    let rdm_uid = driver.get_rdm_uid_with_timeout(timeout)?;
+
+    Ok(DmxController::new(driver, &DmxControllerConfig { rdm_uid }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialized_message(label: u8, data: &[u8]) -> Vec<u8> {
+        let mut buffer = [0u8; MAX_DATA_LENGTH + MIN_PACKAGE_SIZE];
+        let message_size = serialize_message_into(label, data, &mut buffer).unwrap();
+
+        buffer[..message_size].to_vec()
+    }
+
+    #[test]
+    fn deserialize_rejects_truncated_message() {
+        let message = serialized_message(SEND_DMX_PACKET_REQUEST, &[0, 1, 2]);
+
+        for length in 0..message.len() {
+            assert_eq!(EnttecMessage::deserialize(&message[..length]), None);
+        }
+    }
+
+    #[test]
+    fn deserialize_rejects_wrong_delimiters() {
+        let mut message = serialized_message(SEND_DMX_PACKET_REQUEST, &[0, 1, 2]);
+        message[0] = 0x00;
+        assert_eq!(EnttecMessage::deserialize(&message), None);
+
+        let mut message = serialized_message(SEND_DMX_PACKET_REQUEST, &[0, 1, 2]);
+        let last = message.len() - 1;
+        message[last] = 0x00;
+        assert_eq!(EnttecMessage::deserialize(&message), None);
+    }
+
+    #[test]
+    fn deserialize_rejects_length_mismatch() {
+        let mut message = serialized_message(SEND_DMX_PACKET_REQUEST, &[0, 1, 2]);
+        message[2] = 4;
+
+        assert_eq!(EnttecMessage::deserialize(&message), None);
+    }
+
+    #[test]
+    fn deserialize_rejects_length_over_600() {
+        let mut message = vec![0u8; MAX_DATA_LENGTH + 1 + MIN_PACKAGE_SIZE];
+        let last = message.len() - 1;
+        message[0] = START_OF_MESSAGE_DELIMITER;
+        message[1] = SEND_DMX_PACKET_REQUEST;
+        message[2..4].copy_from_slice(&(MAX_DATA_LENGTH as u16 + 1).to_le_bytes());
+        message[last] = END_OF_MESSAGE_DELIMITER;
+
+        assert_eq!(EnttecMessage::deserialize(&message), None);
+    }
+
+    #[test]
+    fn serialize_deserialize_round_trip() {
+        for data in [&[][..], &[0, 255, 7], &[0xE7; MAX_DATA_LENGTH]] {
+            let message = EnttecMessage {
+                label: RECEIVED_DMX_PACKET,
+                data: to_message_data(data),
+            };
+
+            assert_eq!(
+                EnttecMessage::deserialize(&message.serialize()),
+                Some(message)
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_into_rejects_too_small_buffer() {
+        let message = EnttecMessage {
+            label: SEND_DMX_PACKET_REQUEST,
+            data: to_message_data(&[0, 1, 2]),
+        };
+        let mut buffer = [0u8; 7];
+
+        assert_eq!(
+            message.serialize_into(&mut buffer),
+            Err(EnttecProError::InvalidArgument)
+        );
+        assert_eq!(buffer, [0; 7]);
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(message.serialize_into(&mut buffer), Ok(8));
+        assert_eq!(
+            &buffer[..],
+            &serialized_message(SEND_DMX_PACKET_REQUEST, &[0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn serialize_message_into_rejects_payload_over_600() {
+        let mut buffer = [0u8; 2 * MAX_DATA_LENGTH];
+
+        assert_eq!(
+            serialize_message_into(
+                SEND_DMX_PACKET_REQUEST,
+                &[0; MAX_DATA_LENGTH + 1],
+                &mut buffer
+            ),
+            Err(EnttecProError::LengthOutOfRange)
+        );
+    }
+
+    fn change_of_state_message(data: &[u8]) -> EnttecMessage {
+        EnttecMessage {
+            label: RECEIVED_DMX_CHANGE_OF_STATE_PACKET,
+            data: to_message_data(data),
+        }
+    }
+
+    #[test]
+    fn changed_channels_decodes_the_changed_bits() {
+        // Starts at slot 8, bits 0, 2 and 39 are set.
+        let message = change_of_state_message(&[1, 0b0000_0101, 0, 0, 0, 0b1000_0000, 11, 22, 33]);
+
+        let changes: Vec<_> = message
+            .changed_channels()
+            .unwrap()
+            .map(|change| (change.slot, change.value))
+            .collect();
+        assert_eq!(changes, [(8, 11), (10, 22), (47, 33)]);
+    }
+
+    #[test]
+    fn changed_channels_with_empty_bitmap_is_empty() {
+        let message = change_of_state_message(&[0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(message.changed_channels().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn changed_channels_rejects_invalid_packets() {
+        // Two bits are set but only one value follows.
+        let message = change_of_state_message(&[0, 0b0000_0011, 0, 0, 0, 0, 5]);
+        assert!(message.changed_channels().is_none());
+
+        let message = change_of_state_message(&[0, 0, 0]);
+        assert!(message.changed_channels().is_none());
+
+        let message = EnttecMessage {
+            label: RECEIVED_DMX_PACKET,
+            data: to_message_data(&[0, 0, 0, 0, 0, 0]),
+        };
+        assert!(message.changed_channels().is_none());
+    }
+
+    #[test]
+    fn changed_channels_stops_at_the_end_of_the_values() {
+        let changed_channels = ChangedChannels {
+            first_slot: 0,
+            changed_bits: &[0b0000_0011, 0, 0, 0, 0],
+            changed_values: [5].iter(),
+            bit: 0,
+        };
+
+        assert_eq!(
+            changed_channels.collect::<Vec<_>>(),
+            [SlotChange { slot: 0, value: 5 }]
+        );
+    }
+}
@@ -0,0 +1,679 @@
+//! Convenience methods for common rdm requests.
+//!
+//! The requests are sent to the root device of the responder. The `_for_sub_device` variants
+//! address one of its sub-devices (1 to 512) or, for SET requests, all of them using
+//! [ALL_SUB_DEVICES].
+
+use crate::{EnttecProDriver, EnttecProError};
+use dmx_rdm::command_class::RequestCommandClass;
+use dmx_rdm::consts::RDM_STATUS_MESSAGE_SIZE;
+use dmx_rdm::dmx_driver::{DiscoveryOption, DmxError, RdmControllerDriver};
+use dmx_rdm::rdm_data::{RdmData, RdmRequestData, RdmResponseData};
+use dmx_rdm::rdm_types::{DeviceInfo, DiscoveryMuteResponse, StatusMessage, StatusType};
+use dmx_rdm::types::{DataPack, ResponseType};
+use dmx_rdm::unique_identifier::{PackageAddress, UniqueIdentifier};
+use libftd2xx::TimeoutError;
+
+const PID_DISC_UNIQUE_BRANCH: u16 = 0x0001;
+const PID_DISC_MUTE: u16 = 0x0002;
+const PID_DISC_UN_MUTE: u16 = 0x0003;
+const PID_PROXIED_DEVICES: u16 = 0x0010;
+const PID_QUEUED_MESSAGE: u16 = 0x0020;
+const PID_STATUS_MESSAGES: u16 = 0x0030;
+const PID_DEVICE_INFO: u16 = 0x0060;
+const PID_DEVICE_LABEL: u16 = 0x0082;
+const PID_SOFTWARE_VERSION_LABEL: u16 = 0x00C0;
+const PID_DMX_PERSONALITY: u16 = 0x00E0;
+const PID_SENSOR_VALUE: u16 = 0x0201;
+const PID_DMX_START_ADDRESS: u16 = 0x00F0;
+const PID_IDENTIFY_DEVICE: u16 = 0x1000;
+const MAX_SUB_DEVICE: u16 = 0x0200;
+const LOWEST_DEVICE_UID: u64 = 0x0000_0000_0001;
+const HIGHEST_DEVICE_UID: u64 = 0xFFFF_FFFF_FFFE;
+const MAX_LABEL_LENGTH: usize = 32;
+
+/// The sub-device id that addresses the root device of a responder.
+pub const ROOT_DEVICE: u16 = 0x0000;
+/// The sub-device id that addresses all sub-devices of a responder. Only valid for SET requests.
+pub const ALL_SUB_DEVICES: u16 = 0xFFFF;
+
+/// The reading of a sensor, received by [EnttecProDriver::get_sensor_value].
+///
+/// The unit and prefix of the values are described by the sensor definition of the responder.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SensorValue {
+    /// The number of the sensor.
+    pub sensor: u8,
+    /// The current value.
+    pub present_value: i16,
+    /// The lowest value since the last reset or `0` if the sensor doesn't record it.
+    pub lowest_detected_value: i16,
+    /// The highest value since the last reset or `0` if the sensor doesn't record it.
+    pub highest_detected_value: i16,
+    /// The value that was recorded last or `0` if the sensor doesn't support recording.
+    pub recorded_value: i16,
+}
+
+impl SensorValue {
+    fn deserialize(buffer: &[u8]) -> Option<Self> {
+        if buffer.len() != 9 {
+            return None;
+        }
+
+        let read_i16 = |index: usize| i16::from_be_bytes([buffer[index], buffer[index + 1]]);
+
+        Some(Self {
+            sensor: buffer[0],
+            present_value: read_i16(1),
+            lowest_detected_value: read_i16(3),
+            highest_detected_value: read_i16(5),
+            recorded_value: read_i16(7),
+        })
+    }
+}
+
+impl EnttecProDriver {
+    /// Get the device info of the root device of an rdm device.
+    pub fn get_device_info(&mut self, uid: UniqueIdentifier) -> Result<DeviceInfo, EnttecProError> {
+        self.get_device_info_for_sub_device(uid, ROOT_DEVICE)
+    }
+
+    /// Same as [EnttecProDriver::get_device_info] for a sub-device.
+    pub fn get_device_info_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+    ) -> Result<DeviceInfo, EnttecProError> {
+        let response = self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::GetCommand,
+            PID_DEVICE_INFO,
+            &[],
+        )?;
+
+        DeviceInfo::deserialize(&response).or(Err(EnttecProError::InvalidResponse))
+    }
+
+    /// Get the label the user assigned to the root device of an rdm device.
+    pub fn get_device_label(&mut self, uid: UniqueIdentifier) -> Result<String, EnttecProError> {
+        self.get_device_label_for_sub_device(uid, ROOT_DEVICE)
+    }
+
+    /// Same as [EnttecProDriver::get_device_label] for a sub-device.
+    pub fn get_device_label_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+    ) -> Result<String, EnttecProError> {
+        self.get_label(uid, sub_device, PID_DEVICE_LABEL)
+    }
+
+    /// Set the label of the root device of an rdm device. The label can't be longer than
+    /// 32 characters and must only contain ascii characters.
+    pub fn set_device_label(
+        &mut self,
+        uid: UniqueIdentifier,
+        label: &str,
+    ) -> Result<(), EnttecProError> {
+        self.set_device_label_for_sub_device(uid, ROOT_DEVICE, label)
+    }
+
+    /// Same as [EnttecProDriver::set_device_label] for a sub-device.
+    pub fn set_device_label_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        label: &str,
+    ) -> Result<(), EnttecProError> {
+        if label.len() > MAX_LABEL_LENGTH || !label.is_ascii() {
+            return Err(EnttecProError::InvalidArgument);
+        }
+
+        self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::SetCommand,
+            PID_DEVICE_LABEL,
+            label.as_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the human-readable software version of the root device of an rdm device.
+    pub fn get_software_version_label(
+        &mut self,
+        uid: UniqueIdentifier,
+    ) -> Result<String, EnttecProError> {
+        self.get_software_version_label_for_sub_device(uid, ROOT_DEVICE)
+    }
+
+    /// Same as [EnttecProDriver::get_software_version_label] for a sub-device.
+    pub fn get_software_version_label_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+    ) -> Result<String, EnttecProError> {
+        self.get_label(uid, sub_device, PID_SOFTWARE_VERSION_LABEL)
+    }
+
+    /// Set the dmx start address of the root device of an rdm device. The address has to be
+    /// between 1 and 512.
+    pub fn set_dmx_start_address(
+        &mut self,
+        uid: UniqueIdentifier,
+        start_address: u16,
+    ) -> Result<(), EnttecProError> {
+        self.set_dmx_start_address_for_sub_device(uid, ROOT_DEVICE, start_address)
+    }
+
+    /// Same as [EnttecProDriver::set_dmx_start_address] for a sub-device.
+    pub fn set_dmx_start_address_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        start_address: u16,
+    ) -> Result<(), EnttecProError> {
+        if !(1..=512).contains(&start_address) {
+            return Err(EnttecProError::InvalidArgument);
+        }
+
+        self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::SetCommand,
+            PID_DMX_START_ADDRESS,
+            &start_address.to_be_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Set the dmx personality (channel layout) of the root device of an rdm device.
+    /// Personalities start at 1.
+    ///
+    /// The amount of personalities is requested from the device first, so personalities it
+    /// doesn't support are refused without sending the request.
+    pub fn set_dmx_personality(
+        &mut self,
+        uid: UniqueIdentifier,
+        personality: u8,
+    ) -> Result<(), EnttecProError> {
+        self.set_dmx_personality_for_sub_device(uid, ROOT_DEVICE, personality)
+    }
+
+    /// Same as [EnttecProDriver::set_dmx_personality] for a sub-device.
+    pub fn set_dmx_personality_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        personality: u8,
+    ) -> Result<(), EnttecProError> {
+        // The low byte of the personality field contains the amount of personalities.
+        let personality_count = self
+            .get_device_info_for_sub_device(uid, sub_device)?
+            .dmx_personality as u8;
+        if !(1..=personality_count).contains(&personality) {
+            return Err(EnttecProError::InvalidArgument);
+        }
+
+        self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::SetCommand,
+            PID_DMX_PERSONALITY,
+            &[personality],
+        )?;
+
+        Ok(())
+    }
+
+    /// Turn the identify mode (led for searching) of the root device of an rdm device on or off.
+    pub fn identify(&mut self, uid: UniqueIdentifier, enabled: bool) -> Result<(), EnttecProError> {
+        self.identify_for_sub_device(uid, ROOT_DEVICE, enabled)
+    }
+
+    /// Same as [EnttecProDriver::identify] for a sub-device.
+    pub fn identify_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        enabled: bool,
+    ) -> Result<(), EnttecProError> {
+        self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::SetCommand,
+            PID_IDENTIFY_DEVICE,
+            &[enabled as u8],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the current reading of a sensor of the root device of an rdm device. The amount of
+    /// sensors is reported in the [DeviceInfo].
+    pub fn get_sensor_value(
+        &mut self,
+        uid: UniqueIdentifier,
+        sensor: u8,
+    ) -> Result<SensorValue, EnttecProError> {
+        self.get_sensor_value_for_sub_device(uid, ROOT_DEVICE, sensor)
+    }
+
+    /// Same as [EnttecProDriver::get_sensor_value] for a sub-device.
+    pub fn get_sensor_value_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        sensor: u8,
+    ) -> Result<SensorValue, EnttecProError> {
+        // 0xFF addresses all sensors and is only valid for SET requests.
+        if sensor == 0xFF {
+            return Err(EnttecProError::InvalidArgument);
+        }
+
+        let response = self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::GetCommand,
+            PID_SENSOR_VALUE,
+            &[sensor],
+        )?;
+
+        SensorValue::deserialize(&response).ok_or(EnttecProError::InvalidResponse)
+    }
+
+    /// Get the status messages of an rdm device that are at least as severe as `status_type`.
+    /// Use [StatusType::StatusGetLastMessage] to get the previously sent messages again.
+    ///
+    /// The messages of all sub-devices are reported by the root device, so this requests them
+    /// from the root device.
+    pub fn get_status_messages(
+        &mut self,
+        uid: UniqueIdentifier,
+        status_type: StatusType,
+    ) -> Result<Vec<StatusMessage>, EnttecProError> {
+        self.get_status_messages_for_sub_device(uid, ROOT_DEVICE, status_type)
+    }
+
+    /// Same as [EnttecProDriver::get_status_messages] for a sub-device.
+    pub fn get_status_messages_for_sub_device(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        status_type: StatusType,
+    ) -> Result<Vec<StatusMessage>, EnttecProError> {
+        let response = self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::GetCommand,
+            PID_STATUS_MESSAGES,
+            &[status_type as u8],
+        )?;
+
+        if response.len() % RDM_STATUS_MESSAGE_SIZE != 0 {
+            return Err(EnttecProError::InvalidResponse);
+        }
+
+        response
+            .chunks_exact(RDM_STATUS_MESSAGE_SIZE)
+            .map(|message| {
+                StatusMessage::deserialize(message).or(Err(EnttecProError::InvalidResponse))
+            })
+            .collect()
+    }
+
+    /// Polls all queued messages of an rdm device until the responder reports that no messages
+    /// are left.
+    ///
+    /// If there are no queued messages, the responder answers with its status messages instead.
+    /// That response is included as the last element in the result.
+    ///
+    /// Queued messages are always requested from the root device, since they include the
+    /// messages of all sub-devices.
+    pub fn poll_queued_messages(
+        &mut self,
+        uid: UniqueIdentifier,
+    ) -> Result<Vec<RdmData>, EnttecProError> {
+        let mut messages = Vec::new();
+
+        // The message count is an u8, so a well-behaving responder can't queue more than that.
+        for _ in 0..=u8::MAX {
+            let response = self.rdm_transaction(
+                uid,
+                ROOT_DEVICE,
+                RequestCommandClass::GetCommand,
+                PID_QUEUED_MESSAGE,
+                &[StatusType::StatusAdvisory as u8],
+            )?;
+            check_response_type(&response)?;
+
+            let queue_empty =
+                response.parameter_id == PID_STATUS_MESSAGES || response.message_count == 0;
+            messages.push(RdmData::Response(response));
+
+            if queue_empty {
+                return Ok(messages);
+            }
+        }
+
+        Err(EnttecProError::InvalidResponse)
+    }
+
+    /// Discovers all rdm devices on the line.
+    ///
+    /// Un-mutes every device, then searches the uid space using discovery unique branch
+    /// requests and mutes each device that was found. Devices that don't answer the mute
+    /// request are left out of the result.
+    ///
+    /// Devices behind managed proxies don't answer the discovery themselves, so the proxies
+    /// are asked for them using [EnttecProDriver::get_proxied_devices] and they are included
+    /// in the result as well.
+    pub fn discover_all(&mut self) -> Result<Vec<UniqueIdentifier>, EnttecProError> {
+        let request = self.next_request(
+            PackageAddress::Broadcast,
+            ROOT_DEVICE,
+            RequestCommandClass::DiscoveryCommand,
+            PID_DISC_UN_MUTE,
+            &[],
+        )?;
+        self.write_rdm(&RdmData::Request(request))?;
+
+        let mut uids = Vec::new();
+        let mut managed_proxies = Vec::new();
+        self.discover_branch(
+            LOWEST_DEVICE_UID,
+            HIGHEST_DEVICE_UID,
+            &mut uids,
+            &mut managed_proxies,
+        )?;
+
+        for proxy in managed_proxies {
+            for uid in self.get_proxied_devices(proxy)? {
+                if !uids.contains(&uid) {
+                    uids.push(uid);
+                }
+            }
+        }
+
+        Ok(uids)
+    }
+
+    /// Get the uids of the devices a managed proxy answers for. The list can span several
+    /// responses, which are all requested.
+    ///
+    /// The proxied devices are a property of the proxy itself, so they are always requested
+    /// from the root device.
+    pub fn get_proxied_devices(
+        &mut self,
+        uid: UniqueIdentifier,
+    ) -> Result<Vec<UniqueIdentifier>, EnttecProError> {
+        const UID_SIZE: usize = 6;
+
+        let mut proxied_devices = Vec::new();
+
+        // Limits the amount of overflowing responses, so a broken proxy can't keep this looping.
+        for _ in 0..=u8::MAX {
+            let response = self.rdm_transaction(
+                uid,
+                ROOT_DEVICE,
+                RequestCommandClass::GetCommand,
+                PID_PROXIED_DEVICES,
+                &[],
+            )?;
+
+            if response.parameter_id != PID_PROXIED_DEVICES {
+                return Err(EnttecProError::InvalidResponse);
+            }
+
+            check_response_type(&response)?;
+
+            if response.parameter_data.len() % UID_SIZE != 0 {
+                return Err(EnttecProError::InvalidResponse);
+            }
+
+            for uid_bytes in response.parameter_data.chunks_exact(UID_SIZE) {
+                // Can't fail, since the chunks have the correct size.
+                let uid_bytes = uid_bytes
+                    .try_into()
+                    .or(Err(EnttecProError::InvalidResponse))?;
+                match PackageAddress::from_bytes(uid_bytes) {
+                    PackageAddress::Device(proxied_device) => proxied_devices.push(proxied_device),
+                    _ => return Err(EnttecProError::InvalidResponse),
+                }
+            }
+
+            if response.response_type != ResponseType::ResponseTypeAckOverflow {
+                return Ok(proxied_devices);
+            }
+        }
+
+        Err(EnttecProError::InvalidResponse)
+    }
+
+    /// Sends an rdm request and returns the parameter data of the acknowledged response.
+    fn rdm_request(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        command_class: RequestCommandClass,
+        parameter_id: u16,
+        parameter_data: &[u8],
+    ) -> Result<DataPack, EnttecProError> {
+        let response =
+            self.rdm_transaction(uid, sub_device, command_class, parameter_id, parameter_data)?;
+
+        if response.parameter_id != parameter_id {
+            return Err(EnttecProError::InvalidResponse);
+        }
+
+        check_response_type(&response)?;
+
+        // None of the supported parameters are big enough to overflow.
+        if response.response_type == ResponseType::ResponseTypeAckOverflow {
+            return Err(EnttecProError::InvalidResponse);
+        }
+
+        Ok(response.parameter_data)
+    }
+
+    /// Finds all un-muted devices with an uid between `lower_bound` and `upper_bound`.
+    fn discover_branch(
+        &mut self,
+        lower_bound: u64,
+        upper_bound: u64,
+        uids: &mut Vec<UniqueIdentifier>,
+        managed_proxies: &mut Vec<UniqueIdentifier>,
+    ) -> Result<(), EnttecProError> {
+        let mut parameter_data = [0u8; 12];
+        parameter_data[..6].copy_from_slice(&lower_bound.to_be_bytes()[2..]);
+        parameter_data[6..].copy_from_slice(&upper_bound.to_be_bytes()[2..]);
+
+        let request = self.next_request(
+            PackageAddress::Broadcast,
+            ROOT_DEVICE,
+            RequestCommandClass::DiscoveryCommand,
+            PID_DISC_UNIQUE_BRANCH,
+            &parameter_data,
+        )?;
+        self.write_rdm(&RdmData::Request(request))?;
+
+        let discovery_option =
+            self.receive_rdm_discovery_response()
+                .map_err(|error| match error {
+                    DmxError::DriverError(error) => error,
+                    _ => EnttecProError::InvalidResponse,
+                })?;
+
+        match discovery_option {
+            DiscoveryOption::NoDevice => Ok(()),
+            DiscoveryOption::Collision => {
+                if upper_bound == lower_bound {
+                    return Ok(());
+                }
+
+                let middle = lower_bound + (upper_bound - lower_bound) / 2;
+                self.discover_branch(lower_bound, middle, uids, managed_proxies)?;
+                self.discover_branch(middle + 1, upper_bound, uids, managed_proxies)
+            }
+            DiscoveryOption::Found(uid) => {
+                let response = match self.rdm_transaction(
+                    uid,
+                    ROOT_DEVICE,
+                    RequestCommandClass::DiscoveryCommand,
+                    PID_DISC_MUTE,
+                    &[],
+                ) {
+                    Ok(response) => response,
+                    Err(EnttecProError::FtdiError(TimeoutError::Timeout { .. })) => return Ok(()),
+                    Err(error) => return Err(error),
+                };
+
+                if !uids.contains(&uid) {
+                    uids.push(uid);
+                }
+
+                let is_managed_proxy = DiscoveryMuteResponse::deserialize(&response.parameter_data)
+                    .is_ok_and(|mute_response| mute_response.managed_proxy);
+                if is_managed_proxy && !managed_proxies.contains(&uid) {
+                    managed_proxies.push(uid);
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Requests a text parameter.
+    ///
+    /// Labels aren't null terminated, but some responders pad them with nulls anyway, so the
+    /// label ends at the first null. Labels longer than the 32 characters the spec allows are
+    /// truncated.
+    fn get_label(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        parameter_id: u16,
+    ) -> Result<String, EnttecProError> {
+        let response = self.rdm_request(
+            uid,
+            sub_device,
+            RequestCommandClass::GetCommand,
+            parameter_id,
+            &[],
+        )?;
+
+        let label = &response[..response.len().min(MAX_LABEL_LENGTH)];
+        let label = label.split(|&byte| byte == 0).next().unwrap_or_default();
+
+        // The spec only allows ascii, this keeps labels of misbehaving responders readable.
+        Ok(String::from_utf8_lossy(label).into_owned())
+    }
+
+    /// Sends an rdm request and returns the response with the matching transaction number.
+    fn rdm_transaction(
+        &mut self,
+        uid: UniqueIdentifier,
+        sub_device: u16,
+        command_class: RequestCommandClass,
+        parameter_id: u16,
+        parameter_data: &[u8],
+    ) -> Result<RdmResponseData, EnttecProError> {
+        let sub_device_valid = match sub_device {
+            ALL_SUB_DEVICES => command_class == RequestCommandClass::SetCommand,
+            sub_device => sub_device <= MAX_SUB_DEVICE,
+        };
+        if !sub_device_valid {
+            return Err(EnttecProError::InvalidArgument);
+        }
+
+        let request = self.next_request(
+            PackageAddress::Device(uid),
+            sub_device,
+            command_class,
+            parameter_id,
+            parameter_data,
+        )?;
+        self.write_rdm(&RdmData::Request(request))?;
+
+        let response = loop {
+            let response = match self.read_rdm_with_timeout(self.rdm_response_timeouts.initial)? {
+                RdmData::Request(_) => return Err(EnttecProError::InvalidResponse),
+                RdmData::Response(response) => response,
+            };
+
+            if response.transaction_number == self.transaction_number {
+                break response;
+            }
+        };
+
+        // Responders differ in which sub-device they report for a request to all sub-devices.
+        let sub_device_matches = sub_device == ALL_SUB_DEVICES || response.sub_device == sub_device;
+        if response.source_uid != uid || !sub_device_matches {
+            return Err(EnttecProError::InvalidResponse);
+        }
+
+        Ok(response)
+    }
+
+    /// Builds a request with the uid of the widget as source and the next transaction number.
+    fn next_request(
+        &mut self,
+        destination_uid: PackageAddress,
+        sub_device: u16,
+        command_class: RequestCommandClass,
+        parameter_id: u16,
+        parameter_data: &[u8],
+    ) -> Result<RdmRequestData, EnttecProError> {
+        let source_uid = match self.rdm_uid {
+            Some(rdm_uid) => rdm_uid,
+            None => self.get_rdm_uid()?,
+        };
+
+        self.transaction_number = self.transaction_number.wrapping_add(1);
+
+        Ok(RdmRequestData {
+            destination_uid,
+            source_uid,
+            transaction_number: self.transaction_number,
+            port_id: 0,
+            message_count: 0,
+            sub_device,
+            command_class,
+            parameter_id,
+            parameter_data: DataPack::from_slice(parameter_data)
+                .or(Err(EnttecProError::InvalidArgument))?,
+        })
+    }
+}
+
+/// Converts ACK_TIMER and NACK_REASON responses into errors.
+fn check_response_type(response: &RdmResponseData) -> Result<(), EnttecProError> {
+    match response.response_type {
+        ResponseType::ResponseTypeAck | ResponseType::ResponseTypeAckOverflow => Ok(()),
+        ResponseType::ResponseTypeAckTimer => {
+            let estimated_time = response
+                .parameter_data
+                .as_slice()
+                .try_into()
+                .or(Err(EnttecProError::InvalidResponse))?;
+
+            Err(EnttecProError::NotReady(u16::from_be_bytes(estimated_time)))
+        }
+        ResponseType::ResponseTypeNackReason => {
+            let nack_reason = response
+                .parameter_data
+                .as_slice()
+                .try_into()
+                .or(Err(EnttecProError::InvalidResponse))?;
+
+            Err(EnttecProError::NotAcknowledged(
+                u16::from_be_bytes(nack_reason)
+                    .try_into()
+                    .or(Err(EnttecProError::InvalidResponse))?,
+            ))
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Tests against a connected Enttec DMX Pro with the rdm firmware. Enable the `hardware-tests`
+//! feature and set `ENTTEC_PRO_SERIAL` to the serial number of the widget to run them:
+//!
+//! ```shell
+//! ENTTEC_PRO_SERIAL=EN123456 cargo test -p dmx-rdm-enttec-pro --features hardware-tests
+//! ```
+//!
+//! The discovery test needs at least one rdm device connected to the widget.
+
+#![cfg(feature = "hardware-tests")]
+
+use dmx_rdm_enttec_pro::{create_dmx_controller_from_enttec_pro, EnttecProDriver};
+use libftd2xx::Ftdi;
+use std::sync::Mutex;
+
+// The widget can only be opened once at a time, but the tests run in parallel.
+static WIDGET: Mutex<()> = Mutex::new(());
+
+fn open_widget() -> Ftdi {
+    let serial_number =
+        std::env::var("ENTTEC_PRO_SERIAL").expect("ENTTEC_PRO_SERIAL has to be set");
+
+    Ftdi::with_serial_number(&serial_number).expect("couldn't open the widget")
+}
+
+#[test]
+fn handshake_returns_uid() {
+    let _widget = WIDGET.lock().unwrap_or_else(|error| error.into_inner());
+    let mut driver = EnttecProDriver::new(open_widget()).unwrap();
+
+    let rdm_uid = driver.get_rdm_uid().unwrap();
+    assert_eq!(rdm_uid.manufacturer_uid(), 0x454E);
+}
+
+#[test]
+fn sends_dmx() {
+    let _widget = WIDGET.lock().unwrap_or_else(|error| error.into_inner());
+    let mut dmx_controller = create_dmx_controller_from_enttec_pro(open_widget()).unwrap();
+
+    dmx_controller.send_dmx_package(&[0xFF; 512]).unwrap();
+    dmx_controller.send_dmx_package(&[0x00; 24]).unwrap();
+}
+
+#[test]
+fn discovers_devices() {
+    let _widget = WIDGET.lock().unwrap_or_else(|error| error.into_inner());
+    let mut driver = EnttecProDriver::new(open_widget()).unwrap();
+
+    let uids = driver.discover_all().unwrap();
+    assert!(!uids.is_empty(), "no rdm device was found");
+
+    for uid in uids {
+        driver.get_device_info(uid).unwrap();
+    }
+}
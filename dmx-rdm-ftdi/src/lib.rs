@@ -5,17 +5,96 @@
 //! <div class="warning">This driver won't work with Enttec OpenDMX or Enttec DMX Pro devices.
 //! Refer to the readme for more details.</div>
 
-use dmx_rdm::consts::{DMX_BAUD, INTER_SLOT_TIME_MILLIS};
+use dmx_rdm::consts::{
+    DMX_BAUD, DMX_MAX_PACKAGE_SIZE, INTER_SLOT_TIME_MILLIS, RDM_MAX_DISCOVERY_RESPONSE_SIZE,
+};
+use dmx_rdm::dmx_driver::DiscoveryOption;
 use dmx_rdm::dmx_uart_driver::{
     DmxRecvUartDriver, DmxRespUartDriver, DmxUartDriver, DmxUartDriverError,
 };
-use libftd2xx::{BitsPerWord, FtStatus, Ftdi, FtdiCommon, Parity, StopBits};
-use std::time::{Duration, SystemTime};
+use dmx_rdm_utils::checksum::decode_dub_response;
+pub use dmx_rdm_utils::frame::SlotCountValidation;
+use libftd2xx::{BitsPerWord, DeviceType, FtStatus, Ftdi, FtdiCommon, Parity, StopBits};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Recording of received frames for debugging.
+#[cfg(feature = "recorder")]
+pub mod recorder;
+
+/// How long a write may block before it times out.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// The minimum time between the start of two packages.
+const MIN_PACKAGE_PERIOD: Duration = Duration::from_millis(50);
+
+/// Information about a connected ftdi device.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FtdiDeviceInfo {
+    /// The serial number that can be used to open the device.
+    pub serial_number: String,
+    /// The device description.
+    pub description: String,
+    /// The usb vendor id (typically `0x0403`).
+    pub vendor_id: u16,
+    /// The usb product id.
+    pub product_id: u16,
+}
+
+/// Lists all connected ftdi devices that aren't already opened.
+pub fn list_ftdi_devices() -> Result<Vec<FtdiDeviceInfo>, FtStatus> {
+    Ok(libftd2xx::list_devices()?
+        .into_iter()
+        .filter(|device_info| !device_info.port_open)
+        .map(|device_info| FtdiDeviceInfo {
+            serial_number: device_info.serial_number,
+            description: device_info.description,
+            vendor_id: device_info.vendor_id,
+            product_id: device_info.product_id,
+        })
+        .collect())
+}
 
 pub struct FtdiDriverConfig {
     /// In order to comply with the standard this value has to be set to 2ms.
     /// This is extremely cpu intensive. Most of the time lower rates will suffice but be careful.
     pub latency_timer: Duration,
+    /// Overrides the standard dmx baud rate of 250000.
+    /// Only use this for non-standard installations or test rigs.
+    pub baud_rate: Option<u32>,
+    /// The maximum amount of bytes a single read returns, regardless of the buffer size.
+    /// This prevents a source that never stops sending from locking up the reader.
+    /// Defaults to the size of a full dmx frame including the start code.
+    pub max_frame_size: usize,
+    /// The usb transfer size for receiving. Has to be a multiple of 64 between 64 and 65536.
+    /// Smaller sizes reduce the latency while bigger ones improve the throughput.
+    /// `None` keeps the default of the chip.
+    pub usb_transfer_size: Option<u32>,
+    /// How many breaks are sent in front of the first package after opening the device.
+    /// Some fixtures miss the first break after the line was idle and need a second one.
+    /// `0` is treated as `1`. Receivers see every extra break as a break-only frame, which an
+    /// ftdi based receiver reads as a frame with a single 0x00 slot.
+    pub startup_breaks: u8,
+    /// Additional time to wait after clearing the break before the start code is written.
+    /// Clearing the break over usb usually takes long enough for a valid MAB, but some chips
+    /// are fast enough to clip the first slot. The spec allows up to 1s.
+    pub mab_settle_time: Duration,
+    /// The amount of stop bits. Dmx requires two, but some non-compliant devices only send
+    /// and expect one. Only change this for such devices or test rigs.
+    pub stop_bits: StopBits,
+    /// How long no slot has to arrive for a received frame to count as ended. Defaults to the
+    /// inter slot time dmx-rdm uses. Sources with longer gaps between slots need a longer
+    /// timeout, otherwise their frames get truncated. The spec allows gaps of up to 1s.
+    pub inter_slot_timeout: Duration,
+    /// Splits writes into chunks of this many bytes and waits for the transmit queue to drop
+    /// below the chunk size before writing the next one. This gives backpressure when sending
+    /// a lot of data instead of blocking inside of a single big write.
+    /// `None` writes everything at once.
+    pub write_chunk_size: Option<usize>,
+    /// What happens when [DmxRespUartDriver::write_frames] gets more than 512 slots after the
+    /// start code. Defaults to [SlotCountValidation::Strict], which rejects the frame with
+    /// [FtdiDriverError::FrameTooLong].
+    pub slot_count_validation: SlotCountValidation,
 }
 
 impl Default for FtdiDriverConfig {
@@ -23,40 +102,623 @@ impl Default for FtdiDriverConfig {
         Self {
             // this puts a lot of work on the kernel but complies with the standard
             latency_timer: Duration::from_millis(2),
+            baud_rate: None,
+            max_frame_size: DMX_MAX_PACKAGE_SIZE,
+            usb_transfer_size: None,
+            startup_breaks: 1,
+            mab_settle_time: Duration::ZERO,
+            stop_bits: StopBits::Bits2,
+            inter_slot_timeout: Duration::from_millis(INTER_SLOT_TIME_MILLIS as u64),
+            write_chunk_size: None,
+            slot_count_validation: SlotCountValidation::Strict,
+        }
+    }
+}
+
+impl FtdiDriverConfig {
+    /// Returns the default config adjusted to the chip.
+    ///
+    /// The high-speed chips (FT232H, FT2232H and FT4232H) handle a latency timer of 1ms
+    /// without dropping data, which halves the delay for receiving rdm responses. All other
+    /// chips use the default config.
+    pub fn for_device_type(device_type: DeviceType) -> Self {
+        let mut config = Self::default();
+
+        if matches!(
+            device_type,
+            DeviceType::FT232H | DeviceType::FT2232H | DeviceType::FT4232H
+        ) {
+            config.latency_timer = Duration::from_millis(1);
+        }
+
+        config
+    }
+
+    /// Returns a builder that starts with the default config.
+    pub fn builder() -> FtdiDriverConfigBuilder {
+        FtdiDriverConfigBuilder {
+            config: Self::default(),
+        }
+    }
+}
+
+/// An invalid value passed to the [FtdiDriverConfigBuilder].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FtdiConfigError {
+    /// The latency timer is longer than 255ms.
+    LatencyTimerOutOfRange,
+    /// The baud rate is zero.
+    InvalidBaudRate,
+    /// The maximum frame size is zero.
+    InvalidMaxFrameSize,
+    /// The usb transfer size isn't a multiple of 64 between 64 and 65536.
+    InvalidUsbTransferSize,
+    /// The MAB settle time is 1s or longer.
+    MabSettleTimeOutOfRange,
+    /// The inter slot timeout is zero or longer than 1s.
+    InterSlotTimeoutOutOfRange,
+    /// The write chunk size is zero.
+    InvalidWriteChunkSize,
+}
+
+impl Display for FtdiConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FtdiConfigError::LatencyTimerOutOfRange => {
+                write!(f, "latency timer has to be between 0ms and 255ms")
+            }
+            FtdiConfigError::InvalidBaudRate => write!(f, "baud rate can't be zero"),
+            FtdiConfigError::InvalidMaxFrameSize => write!(f, "max frame size can't be zero"),
+            FtdiConfigError::InvalidUsbTransferSize => write!(
+                f,
+                "usb transfer size has to be a multiple of 64 between 64 and 65536"
+            ),
+            FtdiConfigError::MabSettleTimeOutOfRange => {
+                write!(f, "mab settle time has to be shorter than 1s")
+            }
+            FtdiConfigError::InterSlotTimeoutOutOfRange => {
+                write!(f, "inter slot timeout has to be between 0s and 1s")
+            }
+            FtdiConfigError::InvalidWriteChunkSize => write!(f, "write chunk size can't be zero"),
+        }
+    }
+}
+
+impl Error for FtdiConfigError {}
+
+/// An error of the [FtdiDriver].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FtdiDriverError {
+    /// The ftdi library reported an error.
+    Ftdi(FtStatus),
+    /// The frame has more than 512 slots after the start code.
+    /// See [FtdiDriverConfig::slot_count_validation].
+    FrameTooLong,
+}
+
+impl From<FtStatus> for FtdiDriverError {
+    fn from(value: FtStatus) -> Self {
+        Self::Ftdi(value)
+    }
+}
+
+impl Display for FtdiDriverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FtdiDriverError::Ftdi(error) => error.fmt(f),
+            FtdiDriverError::FrameTooLong => {
+                write!(f, "frame has more than 512 slots after the start code")
+            }
+        }
+    }
+}
+
+impl Error for FtdiDriverError {}
+
+/// Builder for [FtdiDriverConfig] that validates the values in [FtdiDriverConfigBuilder::build].
+pub struct FtdiDriverConfigBuilder {
+    config: FtdiDriverConfig,
+}
+
+impl FtdiDriverConfigBuilder {
+    /// See [FtdiDriverConfig::latency_timer].
+    pub fn latency_timer(mut self, latency_timer: Duration) -> Self {
+        self.config.latency_timer = latency_timer;
+        self
+    }
+
+    /// See [FtdiDriverConfig::baud_rate].
+    pub fn baud_rate(mut self, baud_rate: u32) -> Self {
+        self.config.baud_rate = Some(baud_rate);
+        self
+    }
+
+    /// See [FtdiDriverConfig::max_frame_size].
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.config.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// See [FtdiDriverConfig::usb_transfer_size].
+    pub fn usb_transfer_size(mut self, usb_transfer_size: u32) -> Self {
+        self.config.usb_transfer_size = Some(usb_transfer_size);
+        self
+    }
+
+    /// See [FtdiDriverConfig::startup_breaks].
+    pub fn startup_breaks(mut self, startup_breaks: u8) -> Self {
+        self.config.startup_breaks = startup_breaks;
+        self
+    }
+
+    /// See [FtdiDriverConfig::mab_settle_time].
+    pub fn mab_settle_time(mut self, mab_settle_time: Duration) -> Self {
+        self.config.mab_settle_time = mab_settle_time;
+        self
+    }
+
+    /// See [FtdiDriverConfig::stop_bits].
+    pub fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.config.stop_bits = stop_bits;
+        self
+    }
+
+    /// See [FtdiDriverConfig::inter_slot_timeout].
+    pub fn inter_slot_timeout(mut self, inter_slot_timeout: Duration) -> Self {
+        self.config.inter_slot_timeout = inter_slot_timeout;
+        self
+    }
+
+    /// See [FtdiDriverConfig::write_chunk_size].
+    pub fn write_chunk_size(mut self, write_chunk_size: usize) -> Self {
+        self.config.write_chunk_size = Some(write_chunk_size);
+        self
+    }
+
+    /// See [FtdiDriverConfig::slot_count_validation].
+    pub fn slot_count_validation(mut self, slot_count_validation: SlotCountValidation) -> Self {
+        self.config.slot_count_validation = slot_count_validation;
+        self
+    }
+
+    /// Validates the values and returns the config.
+    pub fn build(self) -> Result<FtdiDriverConfig, FtdiConfigError> {
+        // The chip only supports whole milliseconds up to 255ms.
+        if self.config.latency_timer > Duration::from_millis(255) {
+            return Err(FtdiConfigError::LatencyTimerOutOfRange);
+        }
+
+        if self.config.baud_rate == Some(0) {
+            return Err(FtdiConfigError::InvalidBaudRate);
+        }
+
+        if self.config.max_frame_size == 0 {
+            return Err(FtdiConfigError::InvalidMaxFrameSize);
+        }
+
+        if self
+            .config
+            .usb_transfer_size
+            .is_some_and(|usb_transfer_size| !usb_transfer_size_valid(usb_transfer_size))
+        {
+            return Err(FtdiConfigError::InvalidUsbTransferSize);
+        }
+
+        if self.config.mab_settle_time >= Duration::from_secs(1) {
+            return Err(FtdiConfigError::MabSettleTimeOutOfRange);
+        }
+
+        if self.config.inter_slot_timeout.is_zero()
+            || self.config.inter_slot_timeout > Duration::from_secs(1)
+        {
+            return Err(FtdiConfigError::InterSlotTimeoutOutOfRange);
+        }
+
+        if self.config.write_chunk_size == Some(0) {
+            return Err(FtdiConfigError::InvalidWriteChunkSize);
         }
+
+        Ok(self.config)
     }
 }
 
+/// Returned by the callback of [FtdiDriver::run_receive_loop] to decide whether to keep
+/// receiving.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReceiveControl {
+    /// Keep receiving frames.
+    Continue,
+    /// Stop the loop.
+    Stop,
+}
+
+/// The line status of the uart as reported by the ftdi modem status register.
+///
+/// The flags are updated by the device with every usb packet, so poll [FtdiDriver::line_status]
+/// regularly while receiving for a reliable picture of the bus.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct LineStatus {
+    /// A slot was received after the receive buffer had already been full.
+    pub overrun_error: bool,
+    /// A slot was received with the wrong parity.
+    pub parity_error: bool,
+    /// A slot was received without a valid stop bit. Continuous framing errors usually point to
+    /// a shorted or unterminated bus.
+    pub framing_error: bool,
+    /// A break was received.
+    pub break_interrupt: bool,
+    /// The raw line status register.
+    pub raw: u8,
+}
+
 pub struct FtdiDriver {
     serial_port: Ftdi,
     latency_timer_us: u32,
+    max_frame_size: usize,
+    last_break: Option<Instant>,
+    last_package_start: Option<Instant>,
+    startup_breaks: u8,
+    session_started: bool,
+    mab_settle_time: Duration,
+    inter_slot_timeout: Duration,
+    write_chunk_size: Option<usize>,
+    slot_count_validation: SlotCountValidation,
+    device_type: DeviceType,
 }
 
 impl FtdiDriver {
+    /// Creates a driver using [FtdiDriverConfig::for_device_type] for the detected chip.
+    pub fn with_detected_config(mut serial_port: Ftdi) -> Result<Self, FtStatus> {
+        let device_type = serial_port.device_type()?;
+
+        Self::new(serial_port, FtdiDriverConfig::for_device_type(device_type))
+    }
+
     pub fn new(mut serial_port: Ftdi, config: FtdiDriverConfig) -> Result<Self, FtStatus> {
-        serial_port.set_baud_rate(DMX_BAUD)?;
-        serial_port.set_data_characteristics(BitsPerWord::Bits8, StopBits::Bits2, Parity::No)?;
+        // Not every chip reports its type, the driver works without knowing it.
+        let device_type = serial_port.device_type().unwrap_or_default();
+
+        let baud_rate = config.baud_rate.unwrap_or(DMX_BAUD);
+        if baud_rate != DMX_BAUD {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "Using non-standard baud rate of {} instead of {}.",
+                baud_rate,
+                DMX_BAUD
+            );
+        }
+
+        if config.stop_bits != StopBits::Bits2 {
+            #[cfg(feature = "log")]
+            log::warn!("Using a single stop bit instead of the two required by the dmx standard.");
+        }
+
+        serial_port.set_baud_rate(baud_rate)?;
+        serial_port.set_data_characteristics(BitsPerWord::Bits8, config.stop_bits, Parity::No)?;
         serial_port.set_flow_control_none()?;
         serial_port.set_timeouts(
             Duration::from_millis(INTER_SLOT_TIME_MILLIS as u64),
-            Duration::from_secs(1),
+            WRITE_TIMEOUT,
         )?;
         serial_port.set_latency_timer(config.latency_timer)?;
 
+        if let Some(usb_transfer_size) = config.usb_transfer_size {
+            // set_usb_parameters panics on invalid values
+            if !usb_transfer_size_valid(usb_transfer_size) {
+                return Err(FtStatus::INVALID_PARAMETER);
+            }
+
+            serial_port.set_usb_parameters(usb_transfer_size)?;
+        }
+
         Ok(Self {
             serial_port,
             latency_timer_us: config.latency_timer.as_micros() as u32,
+            max_frame_size: config.max_frame_size,
+            last_break: None,
+            last_package_start: None,
+            startup_breaks: config.startup_breaks,
+            session_started: false,
+            mab_settle_time: config.mab_settle_time,
+            inter_slot_timeout: config.inter_slot_timeout,
+            write_chunk_size: config
+                .write_chunk_size
+                // chunks panics on a size of zero
+                .filter(|&write_chunk_size| write_chunk_size != 0),
+            slot_count_validation: config.slot_count_validation,
+            device_type,
+        })
+    }
+
+    /// Checks the wiring by writing a known frame and reading it back.
+    ///
+    /// This requires TX and RX to be shorted externally (or a loopback dongle).
+    /// Returns `false` if nothing was received or the received bytes don't match.
+    ///
+    /// The test frame is sent with a single break even if [FtdiDriverConfig::startup_breaks]
+    /// asks for more, since every extra break would be read back as a 0x00 slot. As the line
+    /// isn't idle anymore afterwards, the startup breaks are skipped for later packages as well.
+    pub fn loopback_test(&mut self) -> Result<bool, FtdiDriverError> {
+        // No zero bytes, since a break shows up as 0x00.
+        const TEST_FRAME: [u8; 11] = [
+            0x55, 0xAA, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0xFF,
+        ];
+        const LOOPBACK_TIMEOUT_US: u32 = 100_000;
+
+        self.purge_rx()?;
+
+        self.session_started = true;
+        match self.write_frames(&TEST_FRAME) {
+            Ok(bytes_written) if bytes_written == TEST_FRAME.len() => {}
+            Ok(_) | Err(DmxUartDriverError::TimeoutError) => return Ok(false),
+            Err(DmxUartDriverError::DriverError(error)) => return Err(error),
+        }
+
+        let mut receive_buffer = [0u8; TEST_FRAME.len()];
+        let bytes_read = match self.read_frames(&mut receive_buffer, LOOPBACK_TIMEOUT_US) {
+            Ok(bytes_read) => bytes_read,
+            Err(DmxUartDriverError::TimeoutError) => return Ok(false),
+            Err(DmxUartDriverError::DriverError(error)) => return Err(error),
+        };
+
+        Ok(receive_buffer[..bytes_read] == TEST_FRAME)
+    }
+
+    /// Picks the longest latency timer that still delivers received bytes within the inter-slot
+    /// time and applies it. Longer latency timers put less work on the cpu.
+    ///
+    /// Like [FtdiDriver::loopback_test] this requires TX and RX to be shorted externally.
+    /// If no candidate meets the deadline (or nothing is received), the previous latency timer is
+    /// kept. Returns the latency timer that is used afterwards.
+    pub fn auto_tune_latency(&mut self) -> Result<Duration, FtStatus> {
+        const CANDIDATES_MS: [u64; 5] = [16, 8, 4, 2, 1];
+        const MEASUREMENTS_PER_CANDIDATE: usize = 5;
+        const RECEIVE_TIMEOUT: Duration = Duration::from_millis(100);
+
+        let deadline = Duration::from_millis(INTER_SLOT_TIME_MILLIS as u64);
+
+        for candidate in CANDIDATES_MS.map(Duration::from_millis) {
+            self.serial_port.set_latency_timer(candidate)?;
+
+            let mut meets_deadline = true;
+            for _ in 0..MEASUREMENTS_PER_CANDIDATE {
+                self.purge_rx()?;
+                self.serial_port.write(&[0x55])?;
+
+                let start_time = Instant::now();
+                let mut received_byte = [0u8; 1];
+                while self.serial_port.read(&mut received_byte)? == 0
+                    && start_time.elapsed() < RECEIVE_TIMEOUT
+                {}
+
+                if start_time.elapsed() > deadline {
+                    meets_deadline = false;
+                    break;
+                }
+            }
+
+            if meets_deadline {
+                self.latency_timer_us = candidate.as_micros() as u32;
+                return Ok(candidate);
+            }
+        }
+
+        let previous_latency_timer = Duration::from_micros(self.latency_timer_us as u64);
+        self.serial_port.set_latency_timer(previous_latency_timer)?;
+
+        Ok(previous_latency_timer)
+    }
+
+    /// Returns the type of the connected chip or [DeviceType::Unknown] if it couldn't be
+    /// detected.
+    pub fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+
+    /// Returns the underlying ftdi handle for functionality this driver doesn't cover
+    /// (e.g. eeprom access).
+    ///
+    /// <div class="warning">Changing the baud rate, data characteristics, timeouts or the latency
+    /// timer breaks the dmx timing of the driver.</div>
+    pub fn raw_handle_mut(&mut self) -> &mut Ftdi {
+        &mut self.serial_port
+    }
+
+    /// Writes a break, the MAB and the already assembled `frame`, which has to start with the
+    /// start code.
+    ///
+    /// Prefer `send_dmx_package` and `send_custom_package`, which take the slots and the start
+    /// code separately. This is the same as [DmxRespUartDriver::write_frames].
+    pub fn write_raw_frame(
+        &mut self,
+        frame: &[u8],
+    ) -> Result<usize, DmxUartDriverError<FtdiDriverError>> {
+        self.write_frames(frame)
+    }
+
+    /// Discards all bytes that are waiting in the receive queue, e.g. the rest of a partially
+    /// read frame. Call this before starting a fresh read cycle.
+    pub fn purge_rx(&mut self) -> Result<(), FtStatus> {
+        self.serial_port.purge_rx()
+    }
+
+    /// Checks if a dmx signal is present by waiting up to `window` for a break.
+    ///
+    /// Keep in mind that most controllers send a frame at least every second, so the window
+    /// shouldn't be much shorter than that.
+    pub fn signal_present(&mut self, window: Duration) -> Result<bool, FtStatus> {
+        // Bytes that are already buffered could be from a signal that is long gone.
+        self.purge_rx()?;
+
+        let start_time = Instant::now();
+        let mut received_byte = [0xFFu8; 1];
+
+        while start_time.elapsed() < window {
+            // a break shows up as a single 0x00 byte
+            let bytes_read = self.serial_port.read(&mut received_byte)?;
+            if bytes_read != 0 && received_byte[0] == 0 {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Reads the line status of the uart. Use this for diagnosing the bus, since framing errors
+    /// don't show up anywhere else until they corrupt a frame.
+    pub fn line_status(&mut self) -> Result<LineStatus, FtStatus> {
+        let modem_status = self.serial_port.modem_status()?;
+
+        Ok(LineStatus {
+            overrun_error: modem_status.overrun_error(),
+            parity_error: modem_status.parity_error(),
+            framing_error: modem_status.framing_error(),
+            break_interrupt: modem_status.break_interrupt(),
+            raw: modem_status.line_status(),
         })
     }
 
-    fn begin_package(&mut self) -> Result<(), FtStatus> {
+    /// Same as [DmxRecvUartDriver::read_frames] but waits until the `deadline` instead of a
+    /// relative timeout. This makes it easy to share a deadline between several reads.
+    pub fn read_frames_until(
+        &mut self,
+        buffer: &mut [u8],
+        deadline: Instant,
+    ) -> Result<usize, DmxUartDriverError<FtdiDriverError>> {
+        let timeout_us = remaining_timeout_us(deadline)?;
+        self.read_frames(buffer, timeout_us)
+    }
+
+    /// Receives the response to a DISC_UNIQUE_BRANCH request and classifies it using
+    /// [classify_discovery_response].
+    pub fn receive_discovery_response(
+        &mut self,
+        timeout_us: u32,
+    ) -> Result<DiscoveryOption, FtdiDriverError> {
+        // Leave room for trailing garbage, so it can be detected as a collision.
+        let mut response = [0u8; RDM_MAX_DISCOVERY_RESPONSE_SIZE + 8];
+
+        let bytes_read = match self.read_frames_no_break(&mut response, timeout_us) {
+            Ok(bytes_read) => bytes_read,
+            Err(DmxUartDriverError::TimeoutError) => return Ok(DiscoveryOption::NoDevice),
+            Err(DmxUartDriverError::DriverError(error)) => return Err(error),
+        };
+
+        Ok(classify_discovery_response(&response[..bytes_read]))
+    }
+
+    /// Receives frames and calls `callback` with every frame (start code included) until the
+    /// callback returns [ReceiveControl::Stop].
+    ///
+    /// Timeouts are ignored, so the loop blocks as long as there is no signal. Errors of the
+    /// ftdi library stop the loop and are returned.
+    pub fn run_receive_loop<F: FnMut(&[u8]) -> ReceiveControl>(
+        &mut self,
+        mut callback: F,
+    ) -> Result<(), FtdiDriverError> {
+        const FRAME_TIMEOUT_US: u32 = 1_000_000;
+
+        let mut frame = [0u8; DMX_MAX_PACKAGE_SIZE];
+        loop {
+            let bytes_read = match self.read_frames(&mut frame, FRAME_TIMEOUT_US) {
+                Ok(bytes_read) => bytes_read,
+                Err(DmxUartDriverError::TimeoutError) => continue,
+                Err(DmxUartDriverError::DriverError(error)) => return Err(error),
+            };
+
+            if callback(&frame[..bytes_read]) == ReceiveControl::Stop {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Same as [DmxRecvUartDriver::read_frames] but also returns the time between the break of
+    /// this frame and the break of the previously read frame. This can be used to measure the
+    /// refresh rate and jitter of a source.
+    ///
+    /// The gap is `None` for the first frame that is read.
+    pub fn read_frames_timed(
+        &mut self,
+        buffer: &mut [u8],
+        timeout_us: u32,
+    ) -> Result<(usize, Option<Duration>), DmxUartDriverError<FtdiDriverError>> {
+        let previous_break = self.last_break;
+        let bytes_read = self.read_frames(buffer, timeout_us)?;
+
+        let gap = previous_break
+            .zip(self.last_break)
+            .map(|(previous_break, last_break)| last_break - previous_break);
+
+        Ok((bytes_read, gap))
+    }
+
+    /// Same as [DmxRecvUartDriver::read_frames_no_break] but waits until the `deadline` instead
+    /// of a relative timeout.
+    pub fn read_frames_no_break_until(
+        &mut self,
+        buffer: &mut [u8],
+        deadline: Instant,
+    ) -> Result<usize, DmxUartDriverError<FtdiDriverError>> {
+        let timeout_us = remaining_timeout_us(deadline)?;
+        self.read_frames_no_break(buffer, timeout_us)
+    }
+
+    /// Writes `buffer` in chunks of `chunk_size`, waiting for the transmit queue to drain
+    /// below `chunk_size` before each chunk.
+    fn write_chunked(
+        &mut self,
+        buffer: &[u8],
+        chunk_size: usize,
+    ) -> Result<usize, DmxUartDriverError<FtdiDriverError>> {
+        let mut bytes_written = 0;
+
+        for chunk in buffer.chunks(chunk_size) {
+            let deadline = Instant::now() + WRITE_TIMEOUT;
+            while self
+                .serial_port
+                .status()
+                .map_err(FtdiDriverError::from)?
+                .ammount_in_tx_queue as usize
+                >= chunk_size
+            {
+                if Instant::now() >= deadline {
+                    return Err(DmxUartDriverError::TimeoutError);
+                }
+            }
+
+            bytes_written += self
+                .serial_port
+                .write(chunk)
+                .map_err(FtdiDriverError::from)?;
+        }
+
+        Ok(bytes_written)
+    }
+
+    fn begin_package(&mut self) -> Result<(), FtdiDriverError> {
         while self.serial_port.status()?.ammount_in_tx_queue != 0 {}
 
-        spin_sleep::sleep(Duration::from_millis(50));
+        // Packages start at most every 50ms. Only the rest of that time is waited, so the time
+        // spent sending and preparing the previous package doesn't add up to drift.
+        if let Some(last_package_start) = self.last_package_start {
+            spin_sleep::sleep(MIN_PACKAGE_PERIOD.saturating_sub(last_package_start.elapsed()));
+        }
+        self.last_package_start = Some(Instant::now());
 
-        self.serial_port.set_break_on()?;
-        self.serial_port.set_break_off()?;
-        // no sleeps since clearing the break already takes long enough 😉
+        let breaks = match self.session_started {
+            true => 1,
+            false => self.startup_breaks.max(1),
+        };
+        self.session_started = true;
+
+        for _ in 0..breaks {
+            self.serial_port.set_break_on()?;
+            self.serial_port.set_break_off()?;
+            // clearing the break usually takes long enough, the settle time is for fast chips
+            if !self.mab_settle_time.is_zero() {
+                spin_sleep::sleep(self.mab_settle_time);
+            }
+        }
 
         Ok(())
     }
@@ -78,15 +740,69 @@ impl FtdiDriver {
     }
 }
 
+fn usb_transfer_size_valid(usb_transfer_size: u32) -> bool {
+    (64..=65536).contains(&usb_transfer_size) && usb_transfer_size.is_multiple_of(64)
+}
+
+/// Classifies the raw bytes received after a DISC_UNIQUE_BRANCH request.
+///
+/// A response only counts as found if it consists of up to 7 preamble bytes, the separator and
+/// a single encoded uid with a valid checksum. Everything else means that multiple devices
+/// answered at the same time. The response is decoded by [decode_dub_response].
+pub fn classify_discovery_response(response: &[u8]) -> DiscoveryOption {
+    if response.is_empty() {
+        return DiscoveryOption::NoDevice;
+    }
+
+    decode_dub_response(response).map_or(DiscoveryOption::Collision, DiscoveryOption::Found)
+}
+
+/// Converts a deadline into the remaining time in µs. Returns a timeout error if the deadline
+/// has already passed, since a timeout of zero has a special meaning for the read functions.
+fn remaining_timeout_us(deadline: Instant) -> Result<u32, DmxUartDriverError<FtdiDriverError>> {
+    let remaining_us = deadline
+        .saturating_duration_since(Instant::now())
+        .as_micros();
+    if remaining_us == 0 {
+        return Err(DmxUartDriverError::TimeoutError);
+    }
+
+    Ok(remaining_us.min(u32::MAX as u128) as u32)
+}
+
 impl DmxUartDriver for FtdiDriver {
-    type DriverError = FtStatus;
+    type DriverError = FtdiDriverError;
 }
 
 impl DmxRespUartDriver for FtdiDriver {
+    /// Writes a break followed by `buffer` (start code followed by the slots).
+    ///
+    /// Frames with more than 512 slots are handled according to
+    /// [FtdiDriverConfig::slot_count_validation].
     fn write_frames(
         &mut self,
         buffer: &[u8],
     ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+        let buffer = match buffer.len() > DMX_MAX_PACKAGE_SIZE {
+            true => match self.slot_count_validation {
+                SlotCountValidation::Strict => {
+                    return Err(DmxUartDriverError::DriverError(
+                        FtdiDriverError::FrameTooLong,
+                    ))
+                }
+                SlotCountValidation::Lenient => {
+                    #[cfg(feature = "log")]
+                    log::warn!(
+                        "Frame has {} slots, truncating it to 512.",
+                        buffer.len() - 1
+                    );
+
+                    &buffer[..DMX_MAX_PACKAGE_SIZE]
+                }
+            },
+            false => buffer,
+        };
+
         self.begin_package()?;
         self.write_frames_no_break(buffer)
     }
@@ -95,62 +811,237 @@ impl DmxRespUartDriver for FtdiDriver {
         &mut self,
         buffer: &[u8],
     ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
-        Ok(self.serial_port.write(buffer)?)
+        match self.write_chunk_size {
+            Some(write_chunk_size) => self.write_chunked(buffer, write_chunk_size),
+            None => Ok(self
+                .serial_port
+                .write(buffer)
+                .map_err(FtdiDriverError::from)?),
+        }
     }
 }
 
 impl DmxRecvUartDriver for FtdiDriver {
+    /// Waits for a break and reads the frame that follows it into `buffer`.
+    ///
+    /// Returns the amount of slots received including the start code, so a frame with 24
+    /// channels returns 25. The break itself isn't part of the count. The frame ends once no
+    /// slot arrived for [FtdiDriverConfig::inter_slot_timeout], the buffer is full or
+    /// [FtdiDriverConfig::max_frame_size] is reached.
+    ///
+    /// A break that isn't followed by any slots returns `Ok(0)`. Since the chip reports a break
+    /// as a 0x00 byte, a break that directly follows another break can't be told apart from a
+    /// null start code though.
     fn read_frames(
         &mut self,
         buffer: &mut [u8],
         timeout_us: u32,
     ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
-        // for some bizarre reason a break shows up as a single 0x00 byte
-        let start_time = SystemTime::now();
-        let mut break_byte = [0xFFu8; 1];
-
+        let buffer_size = buffer.len().min(self.max_frame_size);
         let actual_timeout = self.check_timeout(timeout_us);
 
-        while start_time.elapsed().unwrap().as_micros() < actual_timeout as u128 {
-            let bytes_read = self.serial_port.read(&mut break_byte)?;
-            if bytes_read != 0 && break_byte[0] == 0 {
-                return self.read_frames_no_break(buffer, 1);
-            }
-        }
+        let (break_time, bytes_read) = read_frame(
+            |buffer| Ok(self.serial_port.read(buffer)?),
+            &mut buffer[..buffer_size],
+            actual_timeout,
+            // the first slot follows the break right away, unless it's a break-only frame
+            self.latency_timer_us.max(1),
+            self.inter_slot_timeout,
+        )?;
+        self.last_break = Some(break_time);
 
-        Err(DmxUartDriverError::TimeoutError)
+        Ok(bytes_read)
     }
 
+    /// Reads the slots currently arriving into `buffer` without waiting for a break.
+    ///
+    /// Returns the amount of bytes received. Right after a break the first of them is the
+    /// start code.
     fn read_frames_no_break(
         &mut self,
         buffer: &mut [u8],
         timeout_us: u32,
     ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
-        let buffer_size = buffer.len();
-        let mut head = 0;
-
+        let buffer_size = buffer.len().min(self.max_frame_size);
         let actual_timeout_us = self.check_timeout(timeout_us);
 
-        let mut slot_start = SystemTime::now();
-        while head < buffer_size {
-            let bytes_read = self.serial_port.read(&mut buffer[head..])?;
-            head += bytes_read;
+        read_slots(
+            |buffer| Ok(self.serial_port.read(buffer)?),
+            &mut buffer[..buffer_size],
+            actual_timeout_us,
+            self.inter_slot_timeout,
+        )
+    }
+}
 
-            if head == 0 {
-                if slot_start.elapsed().unwrap().as_micros() < actual_timeout_us as u128 {
-                    continue;
-                }
+/// Reads byte by byte until the break shows up. Returns `false` if there was none within
+/// `timeout_us`.
+fn read_break(
+    mut read: impl FnMut(&mut [u8]) -> Result<usize, FtdiDriverError>,
+    timeout_us: u32,
+) -> Result<bool, FtdiDriverError> {
+    // for some bizarre reason a break shows up as a single 0x00 byte
+    let start_time = Instant::now();
+    let mut break_byte = [0xFFu8; 1];
 
-                return Err(DmxUartDriverError::TimeoutError);
-            }
+    while start_time.elapsed().as_micros() < timeout_us as u128 {
+        let bytes_read = read(&mut break_byte)?;
+        if bytes_read != 0 && break_byte[0] == 0 {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Waits up to `timeout_us` for a break and reads the slots that follow it into `buffer`.
+/// Returns when the break arrived and the amount of bytes read, which is `0` if no slot
+/// arrived within `slot_timeout_us` after the break.
+fn read_frame(
+    mut read: impl FnMut(&mut [u8]) -> Result<usize, FtdiDriverError>,
+    buffer: &mut [u8],
+    timeout_us: u32,
+    slot_timeout_us: u32,
+    inter_slot_timeout: Duration,
+) -> Result<(Instant, usize), DmxUartDriverError<FtdiDriverError>> {
+    if !read_break(&mut read, timeout_us)? {
+        return Err(DmxUartDriverError::TimeoutError);
+    }
+
+    let break_time = Instant::now();
+    match read_slots(read, buffer, slot_timeout_us, inter_slot_timeout) {
+        Ok(bytes_read) => Ok((break_time, bytes_read)),
+        Err(DmxUartDriverError::TimeoutError) => Ok((break_time, 0)),
+        Err(error) => Err(error),
+    }
+}
+
+/// Fills `buffer` with the arriving slots until no slot arrived for `inter_slot_timeout`.
+/// Returns the amount of bytes read, which includes the start code right after a break.
+fn read_slots(
+    mut read: impl FnMut(&mut [u8]) -> Result<usize, FtdiDriverError>,
+    buffer: &mut [u8],
+    timeout_us: u32,
+    inter_slot_timeout: Duration,
+) -> Result<usize, DmxUartDriverError<FtdiDriverError>> {
+    let buffer_size = buffer.len();
+    let mut head = 0;
 
-            if bytes_read > 0 {
-                slot_start = SystemTime::now();
-            } else if slot_start.elapsed().unwrap().as_millis() >= INTER_SLOT_TIME_MILLIS as u128 {
-                break;
+    let mut slot_start = Instant::now();
+    while head < buffer_size {
+        let bytes_read = read(&mut buffer[head..buffer_size])?;
+        head += bytes_read;
+
+        if head == 0 {
+            if slot_start.elapsed().as_micros() < timeout_us as u128 {
+                continue;
             }
+
+            return Err(DmxUartDriverError::TimeoutError);
+        }
+
+        if bytes_read > 0 {
+            slot_start = Instant::now();
+        } else if slot_start.elapsed() >= inter_slot_timeout {
+            break;
+        }
+    }
+
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT_US: u32 = 10_000;
+    const INTER_SLOT_TIMEOUT: Duration = Duration::from_millis(10);
+
+    /// Returns a reader that hands out the chunks like the chip does and nothing afterwards.
+    fn scripted_reader<'a>(
+        chunks: &'a [&'a [u8]],
+    ) -> impl FnMut(&mut [u8]) -> Result<usize, FtdiDriverError> + 'a {
+        let mut chunks = chunks.iter().flat_map(|chunk| [*chunk, &[]]);
+
+        move |buffer| {
+            let chunk = chunks.next().unwrap_or_default();
+            let bytes_read = chunk.len().min(buffer.len());
+            buffer[..bytes_read].copy_from_slice(&chunk[..bytes_read]);
+
+            Ok(bytes_read)
         }
+    }
+
+    #[test]
+    fn frame_count_includes_start_code() {
+        let channels = [0x7Fu8; 24];
+        let chunks: [&[u8]; 4] = [&[0x00], &[0x00], &channels[..10], &channels[10..]];
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        let (_, bytes_read) = read_frame(
+            scripted_reader(&chunks),
+            &mut buffer,
+            TIMEOUT_US,
+            TIMEOUT_US,
+            INTER_SLOT_TIMEOUT,
+        )
+        .unwrap();
+
+        assert_eq!(bytes_read, 25);
+        assert_eq!(buffer[0], 0x00);
+        assert_eq!(&buffer[1..bytes_read], &channels);
+    }
+
+    #[test]
+    fn frame_ends_when_buffer_is_full() {
+        let mut read = scripted_reader(&[&[0x00, 1, 2, 3, 4]]);
+        let mut buffer = [0u8; 3];
+
+        let bytes_read =
+            read_slots(&mut read, &mut buffer, TIMEOUT_US, INTER_SLOT_TIMEOUT).unwrap();
+        assert_eq!(bytes_read, 3);
+    }
+
+    #[test]
+    fn no_slots_times_out() {
+        let mut read = scripted_reader(&[]);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert!(!read_break(&mut read, TIMEOUT_US).unwrap());
+        assert!(matches!(
+            read_slots(&mut read, &mut buffer, TIMEOUT_US, INTER_SLOT_TIMEOUT),
+            Err(DmxUartDriverError::TimeoutError)
+        ));
+    }
+
+    #[test]
+    fn break_without_slots_is_empty_frame() {
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        let (_, bytes_read) = read_frame(
+            scripted_reader(&[&[0x00]]),
+            &mut buffer,
+            TIMEOUT_US,
+            TIMEOUT_US,
+            INTER_SLOT_TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(bytes_read, 0);
+    }
+
+    #[test]
+    fn frame_without_break_times_out() {
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
 
-        Ok(head)
+        assert!(matches!(
+            read_frame(
+                scripted_reader(&[&[0x7F, 0x7F]]),
+                &mut buffer,
+                TIMEOUT_US,
+                TIMEOUT_US,
+                INTER_SLOT_TIMEOUT,
+            ),
+            Err(DmxUartDriverError::TimeoutError)
+        ));
     }
 }
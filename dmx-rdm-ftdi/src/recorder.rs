@@ -0,0 +1,100 @@
+use crate::{FtdiDriver, FtdiDriverError};
+use dmx_rdm::dmx_uart_driver::{DmxRecvUartDriver, DmxUartDriverError};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::time::Instant;
+
+/// An error that occurred while recording a frame.
+#[derive(Debug)]
+pub enum FrameRecorderError {
+    /// Receiving the frame failed.
+    Driver(DmxUartDriverError<FtdiDriverError>),
+    /// Writing the frame to the recording failed.
+    Io(std::io::Error),
+}
+
+impl From<DmxUartDriverError<FtdiDriverError>> for FrameRecorderError {
+    fn from(value: DmxUartDriverError<FtdiDriverError>) -> Self {
+        Self::Driver(value)
+    }
+}
+
+impl From<std::io::Error> for FrameRecorderError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Display for FrameRecorderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameRecorderError::Driver(error) => write!(f, "receiving failed: {}", error),
+            FrameRecorderError::Io(error) => write!(f, "recording failed: {}", error),
+        }
+    }
+}
+
+impl Error for FrameRecorderError {}
+
+/// Wraps a [FtdiDriver] and records every received frame to `writer`, e.g. for attaching a
+/// capture to a bug report.
+///
+/// Every frame is written as its own line, which contains the time since the recorder was
+/// created in µs followed by a space and the frame (start code and slots) in hex:
+///
+/// ```text
+/// 1042 00FF8000
+/// ```
+pub struct FrameRecorder<W: Write> {
+    driver: FtdiDriver,
+    writer: W,
+    start_time: Instant,
+}
+
+impl<W: Write> FrameRecorder<W> {
+    pub fn new(driver: FtdiDriver, writer: W) -> Self {
+        Self {
+            driver,
+            writer,
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Same as [DmxRecvUartDriver::read_frames] but also records the received frame.
+    /// Frames that don't contain any slots aren't recorded.
+    pub fn read_frames(
+        &mut self,
+        buffer: &mut [u8],
+        timeout_us: u32,
+    ) -> Result<usize, FrameRecorderError> {
+        let bytes_read = self.driver.read_frames(buffer, timeout_us)?;
+        if bytes_read == 0 {
+            return Ok(0);
+        }
+
+        write!(self.writer, "{}", self.start_time.elapsed().as_micros())?;
+        write!(self.writer, " ")?;
+        for slot in &buffer[..bytes_read] {
+            write!(self.writer, "{:02X}", slot)?;
+        }
+        writeln!(self.writer)?;
+
+        Ok(bytes_read)
+    }
+
+    /// Flushes the recording.
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.writer.flush()
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut FtdiDriver {
+        &mut self.driver
+    }
+
+    /// Returns the underlying driver and writer.
+    pub fn into_parts(self) -> (FtdiDriver, W) {
+        (self.driver, self.writer)
+    }
+}
@@ -0,0 +1,54 @@
+//! Tests against a connected ftdi rs485 cable. Enable the `hardware-tests` feature and set
+//! `FTDI_SERIAL` to the serial number of the cable to run them:
+//!
+//! ```shell
+//! FTDI_SERIAL=FT123456 cargo test -p dmx-rdm-ftdi --features hardware-tests
+//! ```
+//!
+//! The discovery test needs at least one rdm device connected to the cable.
+
+#![cfg(feature = "hardware-tests")]
+
+use dmx_rdm::dmx_controller::{DmxController, DmxControllerConfig};
+use dmx_rdm::unique_identifier::{PackageAddress, UniqueIdentifier};
+use dmx_rdm::utils::run_full_discovery;
+use dmx_rdm_ftdi::{FtdiDriver, FtdiDriverConfig};
+use libftd2xx::Ftdi;
+use std::sync::Mutex;
+
+// The cable can only be opened once at a time, but the tests run in parallel.
+static CABLE: Mutex<()> = Mutex::new(());
+
+fn open_driver() -> FtdiDriver {
+    let serial_number = std::env::var("FTDI_SERIAL").expect("FTDI_SERIAL has to be set");
+    let serial_port = Ftdi::with_serial_number(&serial_number).expect("couldn't open the cable");
+
+    FtdiDriver::new(serial_port, FtdiDriverConfig::default()).unwrap()
+}
+
+#[test]
+fn sends_dmx() {
+    let _cable = CABLE.lock().unwrap_or_else(|error| error.into_inner());
+    let mut dmx_controller = DmxController::new(open_driver(), &DmxControllerConfig::default());
+
+    dmx_controller.send_dmx_package(&[0xFF; 512]).unwrap();
+    dmx_controller.send_dmx_package(&[0x00; 24]).unwrap();
+}
+
+#[test]
+fn discovers_devices() {
+    let _cable = CABLE.lock().unwrap_or_else(|error| error.into_inner());
+    let mut dmx_controller = DmxController::new(open_driver(), &DmxControllerConfig::default());
+
+    dmx_controller
+        .rdm_disc_un_mute(PackageAddress::Broadcast)
+        .unwrap();
+
+    let mut uids = [UniqueIdentifier::new(1, 1).unwrap(); 32];
+    let devices_found = run_full_discovery(&mut dmx_controller, &mut uids).unwrap();
+    assert_ne!(devices_found, 0, "no rdm device was found");
+
+    for uid in &uids[..devices_found] {
+        dmx_controller.rdm_get_device_info(*uid).unwrap();
+    }
+}
@@ -0,0 +1,22 @@
+use embedded_hal_0_2::timer::{Cancel, CountDown};
+use fugit::{ExtU32, MicrosDurationU32};
+
+/// The countdown the driver uses for its timing. This is implemented for every cancelable
+/// countdown that accepts µs, like the [rp2040_hal::timer::CountDown] of the hal.
+///
+/// The countdown has to be periodic or has to keep reporting that it elapsed until it is
+/// started again.
+pub trait Rp2040CountDown: Cancel {
+    /// Starts the countdown with a timeout in µs.
+    fn start_us(&mut self, timeout_us: u32);
+}
+
+impl<C> Rp2040CountDown for C
+where
+    C: CountDown + Cancel,
+    C::Time: From<MicrosDurationU32>,
+{
+    fn start_us(&mut self, timeout_us: u32) {
+        self.start(timeout_us.micros());
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Rp2040CountDown, Rp2040Driver, Rp2040Uart};
+
+/// Upper bounds (exclusive) of the inter-slot histogram buckets in µs.
+/// The last bucket of [ReadInstrumentation::inter_slot_histogram] counts every gap above that.
+pub const INTER_SLOT_HISTOGRAM_BOUNDS_US: [u64; 7] = [50, 100, 200, 500, 1_000, 2_000, 5_000];
+
+/// Timing measurements of the frame that was last read using `read_frames`.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReadInstrumentation {
+    /// Time between the uart reporting the break and the start code arriving in µs.
+    ///
+    /// The uart only reports a break after the line has been low for a full character, so this
+    /// is the remaining break time plus the MAB and not the full break length.
+    pub break_to_start_code_us: Option<u64>,
+    /// Histogram of the gaps between slots. Bucket `i` counts the gaps shorter than
+    /// [INTER_SLOT_HISTOGRAM_BOUNDS_US]`[i]`.
+    ///
+    /// Slots that were read from the fifo together are counted as back to back.
+    pub inter_slot_histogram: [u32; INTER_SLOT_HISTOGRAM_BOUNDS_US.len() + 1],
+}
+
+impl ReadInstrumentation {
+    fn record_gap(&mut self, gap_us: u64, count: u32) {
+        let bucket = INTER_SLOT_HISTOGRAM_BOUNDS_US
+            .iter()
+            .position(|&bound| gap_us < bound)
+            .unwrap_or(INTER_SLOT_HISTOGRAM_BOUNDS_US.len());
+
+        self.inter_slot_histogram[bucket] += count;
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct InstrumentationState {
+    break_ticks: Option<u64>,
+    last_slot_ticks: Option<u64>,
+    measurements: ReadInstrumentation,
+}
+
+impl<U: Rp2040Uart, C: Rp2040CountDown> Rp2040Driver<'_, U, C> {
+    /// Returns the timing measurements of the frame that was last read using `read_frames`.
+    pub fn instrumentation(&self) -> &ReadInstrumentation {
+        &self.instrumentation.measurements
+    }
+
+    pub(crate) fn instrument_break(&mut self) {
+        let state = &mut self.instrumentation;
+        let timer = match self.measurement_timer {
+            Some(timer) => timer,
+            None => return,
+        };
+
+        state.measurements = ReadInstrumentation::default();
+        state.break_ticks = Some(timer.get_counter().ticks());
+        state.last_slot_ticks = None;
+    }
+
+    pub(crate) fn instrument_slots(&mut self, slots_read: usize) {
+        let state = &mut self.instrumentation;
+        let timer = match self.measurement_timer {
+            Some(timer) => timer,
+            None => return,
+        };
+
+        if slots_read == 0 {
+            return;
+        }
+
+        let now = timer.get_counter().ticks();
+        match state.last_slot_ticks {
+            Some(last_slot_ticks) => state
+                .measurements
+                .record_gap(now.wrapping_sub(last_slot_ticks), 1),
+            None => {
+                state.measurements.break_to_start_code_us = state
+                    .break_ticks
+                    .map(|break_ticks| now.wrapping_sub(break_ticks));
+            }
+        }
+
+        // The remaining slots have been waiting in the fifo.
+        state.measurements.record_gap(0, slots_read as u32 - 1);
+        state.last_slot_ticks = Some(now);
+    }
+}
@@ -3,25 +3,74 @@
 //! This was tested using the [Waveshare Pico-2CH-RS485](https://www.waveshare.com/wiki/Pico-2CH-RS485)
 //! and does not require a pin for switching between receiving and transmitting on the transceiver.
 //! The schematic for this board is also [available](https://files.waveshare.com/upload/0/02/Pico-2CH-RS485.pdf).
+//!
+//! Besides receiving and responding, the driver can be used as an rdm controller by passing it
+//! to the `DmxController` of dmx-rdm. Discovery responses are read without waiting for a break
+//! and colliding responses are reported as a collision instead of a framing error.
+//!
+//! Transceivers that need a DE/RE pin are supported by [Rp2040Driver::enable_direction_pin].
+//!
+//! ## Using both channels
+//! Every driver needs its own countdown, but they can come from the same timer.
+//! `rp2040_hal::Timer` is `Copy` and its countdowns only read the free-running counter,
+//! so two drivers don't influence each other's timing.
+//! ```ignore
+//! let timer = rp2040_hal::Timer::new(pac.TIMER, &mut pac.RESETS, &clocks);
+//!
+//! let mut universe_1 = Rp2040Driver::new(uart0, timer.count_down());
+//! let mut universe_2 = Rp2040Driver::new(uart1, timer.count_down());
+//! ```
+//! The drivers block while reading and writing, so a single core can only serve one channel at
+//! a time. Run the second driver on the second core if both universes have to be received
+//! without gaps.
 
 #![no_std]
 
+use core::convert::Infallible;
 use core::fmt::Formatter;
+use dmx_rdm::consts::{DMX_MAX_PACKAGE_SIZE, DMX_NULL_START, SC_RDM};
 use dmx_rdm::dmx_uart_driver::{
     DmxRecvUartDriver, DmxRespUartDriver, DmxUartDriver, DmxUartDriverError,
 };
-use embedded_hal_0_2::timer::{Cancel, CountDown};
-use fugit::{ExtU32, ExtU64};
-use rp2040_hal::uart::{
-    Enabled, ReadError, ReadErrorType, UartDevice, UartPeripheral, ValidUartPinout,
-};
+use embedded_hal::digital::OutputPin;
+use rp2040_hal::uart::{FifoWatermark, ReadError, ReadErrorType};
+
+mod countdown;
+#[cfg(feature = "instrumentation")]
+mod instrumentation;
+/// In-memory uart and countdown for testing the driver on the host.
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+mod responder;
+mod uart;
+
+pub use countdown::Rp2040CountDown;
+pub use dmx_rdm_utils::frame::SlotCountValidation;
+#[cfg(feature = "instrumentation")]
+pub use instrumentation::{ReadInstrumentation, INTER_SLOT_HISTOGRAM_BOUNDS_US};
+pub use responder::Rp2040Responder;
+pub use uart::Rp2040Uart;
 
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Rp2040DriverError {
     Parity,
     Framing,
-    Overflow,
+    /// The receive fifo overflowed because it wasn't read fast enough.
+    Overflow {
+        /// The amount of slots of the frame (start code included) that were received in front
+        /// of the error and discarded along with it. The hal doesn't report how many bytes were
+        /// lost in the fifo itself.
+        discarded: usize,
+    },
+    /// A frame was written without a start code.
+    EmptyFrame,
+    /// A frame with more than 512 slots after the start code was written.
+    /// See [Rp2040DriverConfig::slot_count_validation].
+    FrameTooLong,
+    /// The start address or the footprint passed to [Rp2040Driver::read_dmx_channels] is
+    /// outside of the dmx universe.
+    InvalidChannelRange,
 }
 
 impl core::fmt::Display for Rp2040DriverError {
@@ -29,116 +78,653 @@ impl core::fmt::Display for Rp2040DriverError {
         match self {
             Rp2040DriverError::Parity => write!(f, "Parity error!"),
             Rp2040DriverError::Framing => write!(f, "Framing error!"),
-            Rp2040DriverError::Overflow => write!(f, "Overflow error!"),
+            Rp2040DriverError::Overflow { discarded } => {
+                write!(f, "Overflow error! ({} bytes discarded)", discarded)
+            }
+            Rp2040DriverError::EmptyFrame => write!(f, "Empty frame!"),
+            Rp2040DriverError::FrameTooLong => write!(f, "Frame too long!"),
+            Rp2040DriverError::InvalidChannelRange => write!(f, "Invalid channel range!"),
         }
     }
 }
 
-pub struct Rp2040Driver<'a, D: UartDevice, P: ValidUartPinout<D>> {
-    uart: UartPeripheral<Enabled, D, P>,
-    countdown: rp2040_hal::timer::CountDown<'a>,
+impl From<ReadError<'_>> for Rp2040DriverError {
+    fn from(value: ReadError<'_>) -> Self {
+        match value.err_type {
+            ReadErrorType::Overrun => Rp2040DriverError::Overflow {
+                discarded: value.discarded.len(),
+            },
+            ReadErrorType::Parity => Rp2040DriverError::Parity,
+            // A break never has a valid stop bit, so it counts as a framing error here.
+            ReadErrorType::Framing | ReadErrorType::Break => Rp2040DriverError::Framing,
+        }
+    }
 }
 
-impl<D: UartDevice, P: ValidUartPinout<D>> Rp2040Driver<'_, D, P> {
-    pub fn new(
-        uart: UartPeripheral<Enabled, D, P>,
-        countdown: rp2040_hal::timer::CountDown,
-    ) -> Rp2040Driver<D, P> {
-        Rp2040Driver { uart, countdown }
+/// The uart errors that occurred while reading a frame using
+/// [Rp2040Driver::read_frames_counting_errors].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReadErrorCounts {
+    /// Slots received with the wrong parity.
+    pub parity: u16,
+    /// Slots received without a valid stop bit.
+    pub framing: u16,
+    /// Times the receive fifo overflowed.
+    pub overrun: u16,
+}
+
+impl ReadErrorCounts {
+    /// Returns `true` if no error occurred.
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
     }
 
-    fn begin_package(&mut self) {
-        self.uart.lowlevel_break_start();
+    /// Counts the error and returns whether the faulty slot was dropped.
+    fn record(&mut self, err_type: &ReadErrorType) -> bool {
+        match err_type {
+            ReadErrorType::Parity => self.parity += 1,
+            ReadErrorType::Framing => self.framing += 1,
+            ReadErrorType::Overrun => {
+                self.overrun += 1;
+                return false;
+            }
+            // A break ends the frame and isn't an error of any slot.
+            ReadErrorType::Break => return false,
+        }
 
-        self.countdown.start(200u64.micros()); // BRK
-        while self.countdown.wait() == Err(nb::Error::WouldBlock) {}
-        self.uart.lowlevel_break_stop();
+        true
+    }
+}
 
-        self.countdown.start(48u64.micros()); // MAB
-        while self.countdown.wait() == Err(nb::Error::WouldBlock) {}
+/// Timing of the direction pin that switches the transceiver between receiving and transmitting.
+#[derive(Debug, Clone)]
+pub struct DirectionPinConfig {
+    /// Time between enabling the transmitter and starting the break in µs.
+    /// This covers the driver enable time of the transceiver.
+    pub tx_enable_setup_us: u32,
+    /// Time between the last stop bit leaving the uart and disabling the transmitter in µs.
+    /// This covers the propagation delay of the transceiver.
+    pub tx_disable_hold_us: u32,
+}
+
+impl Default for DirectionPinConfig {
+    fn default() -> Self {
+        // MAX485-class transceivers need a few µs at most, so this leaves plenty of headroom.
+        Self {
+            tx_enable_setup_us: 10,
+            tx_disable_hold_us: 10,
+        }
     }
 }
 
-impl<D: UartDevice, P: ValidUartPinout<D>> DmxUartDriver for Rp2040Driver<'_, D, P> {
-    type DriverError = Rp2040DriverError;
+/// Passing this as `timeout_us` to the read functions waits for the frame without a timeout.
+/// Once the first slot arrived, the frame still ends after the usual gap between slots.
+///
+/// Any other value is a regular timeout, so the longest timeout is about 71 minutes.
+pub const NO_TIMEOUT: u32 = u32::MAX;
+
+/// Minimum time between the end of a request and the response of a responder in µs.
+const RESPONDER_TURNAROUND_US: u32 = 176;
+
+/// General configuration of the [Rp2040Driver].
+#[derive(Debug, Clone)]
+pub struct Rp2040DriverConfig {
+    /// How long to wait for the response after sending an rdm request in µs.
+    ///
+    /// Responders have to start answering within 2ms, so the default of 2.8ms is the time after
+    /// which the spec considers a response as lost. Lowering it speeds up discovery of fixtures
+    /// that answer quickly, but keep in mind that the spec also requires the controller to wait
+    /// for a minimum time after a lost response before sending the next break.
+    pub rdm_response_timeout_us: u32,
+    /// How many break and MAB sequences are sent in front of the first package of the driver.
+    /// Some fixtures miss the first break after the line was idle and need a second one.
+    /// `0` is treated as `1`.
+    pub startup_breaks: u8,
+    /// The fill level of the receive fifo that triggers the rx interrupt (and dma requests).
+    /// `None` keeps the setting of the uart, which is 16 bytes after reset.
+    ///
+    /// The driver polls the fifo and isn't affected by this. It matters for applications that
+    /// wake up on the rx interrupt to call the driver: a low level wakes them up sooner after
+    /// the first slots arrived, while a high level needs fewer wake ups per frame but leaves
+    /// less room in the 32 byte fifo. A slot takes 44µs at 250kbaud, so at 28 bytes the fifo
+    /// overruns 176µs after the interrupt if it isn't read by then.
+    pub rx_fifo_watermark: Option<RxFifoWatermark>,
+    /// What happens when [DmxRespUartDriver::write_frames] gets more than 512 slots after the
+    /// start code. Defaults to [SlotCountValidation::Strict], which rejects the frame with
+    /// [Rp2040DriverError::FrameTooLong].
+    pub slot_count_validation: SlotCountValidation,
 }
 
-impl<D: UartDevice, P: ValidUartPinout<D>> DmxRecvUartDriver for Rp2040Driver<'_, D, P> {
-    fn read_frames(
+impl Default for Rp2040DriverConfig {
+    fn default() -> Self {
+        Self {
+            rdm_response_timeout_us: 2_800,
+            startup_breaks: 1,
+            rx_fifo_watermark: None,
+            slot_count_validation: SlotCountValidation::Strict,
+        }
+    }
+}
+
+/// The fill level of the receive fifo that triggers the rx interrupt.
+/// See [Rp2040DriverConfig::rx_fifo_watermark].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RxFifoWatermark {
+    Bytes4,
+    Bytes8,
+    Bytes16,
+    Bytes24,
+    Bytes28,
+}
+
+impl From<RxFifoWatermark> for FifoWatermark {
+    fn from(value: RxFifoWatermark) -> Self {
+        match value {
+            RxFifoWatermark::Bytes4 => FifoWatermark::Bytes4,
+            RxFifoWatermark::Bytes8 => FifoWatermark::Bytes8,
+            RxFifoWatermark::Bytes16 => FifoWatermark::Bytes16,
+            RxFifoWatermark::Bytes24 => FifoWatermark::Bytes24,
+            RxFifoWatermark::Bytes28 => FifoWatermark::Bytes28,
+        }
+    }
+}
+
+pub struct Rp2040Driver<'a, U: Rp2040Uart, C: Rp2040CountDown> {
+    uart: U,
+    countdown: C,
+    turnaround_countdown: Option<C>,
+    turnaround_running: bool,
+    break_pending: bool,
+    discovery_response_pending: bool,
+    rdm_response_pending: bool,
+    config: Rp2040DriverConfig,
+    direction_pin: Option<&'a mut dyn OutputPin<Error = Infallible>>,
+    direction_pin_config: DirectionPinConfig,
+    transmitter_enabled: bool,
+    session_started: bool,
+    responder_mode: bool,
+    response_turnaround_pending: bool,
+    #[cfg(any(feature = "defmt", feature = "instrumentation"))]
+    measurement_timer: Option<rp2040_hal::Timer>,
+    #[cfg(feature = "instrumentation")]
+    instrumentation: instrumentation::InstrumentationState,
+}
+
+impl<'a, U: Rp2040Uart, C: Rp2040CountDown> Rp2040Driver<'a, U, C> {
+    pub fn new(uart: U, countdown: C) -> Rp2040Driver<'a, U, C> {
+        Self::new_with_config(uart, countdown, Rp2040DriverConfig::default())
+    }
+
+    pub fn new_with_config(
+        mut uart: U,
+        countdown: C,
+        config: Rp2040DriverConfig,
+    ) -> Rp2040Driver<'a, U, C> {
+        if let Some(rx_fifo_watermark) = config.rx_fifo_watermark {
+            uart.set_rx_watermark(rx_fifo_watermark.into());
+        }
+
+        Rp2040Driver {
+            uart,
+            countdown,
+            turnaround_countdown: None,
+            turnaround_running: false,
+            break_pending: false,
+            discovery_response_pending: false,
+            rdm_response_pending: false,
+            config,
+            direction_pin: None,
+            direction_pin_config: DirectionPinConfig::default(),
+            transmitter_enabled: false,
+            session_started: false,
+            responder_mode: false,
+            response_turnaround_pending: false,
+            #[cfg(any(feature = "defmt", feature = "instrumentation"))]
+            measurement_timer: None,
+            #[cfg(feature = "instrumentation")]
+            instrumentation: Default::default(),
+        }
+    }
+
+    /// Creates a driver with a second countdown that is only used for turnaround measurement.
+    /// See [Rp2040Driver::with_turnaround_countdown].
+    pub fn new_with_turnaround_countdown(
+        uart: U,
+        countdown: C,
+        turnaround_countdown: C,
+    ) -> Rp2040Driver<'a, U, C> {
+        Self::new(uart, countdown).with_turnaround_countdown(turnaround_countdown)
+    }
+
+    /// Adds a second countdown that is only used for turnaround measurement, e.g. to a driver
+    /// created using [Rp2040Driver::new_with_config].
+    ///
+    /// The first countdown is still used for break, MAB and read timeouts, so the turnaround
+    /// deadline keeps running while frames are being read or written.
+    pub fn with_turnaround_countdown(mut self, turnaround_countdown: C) -> Self {
+        self.turnaround_countdown = Some(turnaround_countdown);
+        self
+    }
+
+    /// Starts the turnaround countdown (e.g. 176µs after the end of an RDM request).
+    /// Returns `false` if the driver has no turnaround countdown.
+    pub fn start_turnaround(&mut self, timeout_us: u32) -> bool {
+        match self.turnaround_countdown {
+            Some(ref mut turnaround_countdown) => {
+                turnaround_countdown.start_us(timeout_us);
+                self.turnaround_running = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether the turnaround countdown has elapsed. A countdown that was never started
+    /// counts as elapsed. Returns `None` if the driver has no turnaround countdown.
+    pub fn turnaround_elapsed(&mut self) -> Option<bool> {
+        let turnaround_countdown = self.turnaround_countdown.as_mut()?;
+
+        if !self.turnaround_running {
+            return Some(true);
+        }
+
+        if turnaround_countdown.wait() == Err(nb::Error::WouldBlock) {
+            return Some(false);
+        }
+
+        // The countdown is periodic, so stop it to keep reporting it as elapsed.
+        turnaround_countdown.cancel().ok();
+        self.turnaround_running = false;
+
+        Some(true)
+    }
+
+    /// Blocks until the turnaround countdown has elapsed and stops it.
+    /// Returns immediately if the driver has no turnaround countdown.
+    pub fn wait_turnaround(&mut self) {
+        while self.turnaround_elapsed() == Some(false) {}
+    }
+
+    /// Returns `true` if the uart isn't transmitting (BUSY flag cleared) and nothing is
+    /// waiting in the receive fifo (RXFE flag set). Nothing gets read from the fifo.
+    ///
+    /// The uart only reports a break once the line has been low for a full character (44µs),
+    /// so a break that just started still counts as idle. Received bytes and breaks keep the
+    /// line from counting as idle until they are read.
+    pub fn line_idle(&self) -> bool {
+        !self.uart.uart_is_busy() && !self.uart.uart_is_readable()
+    }
+
+    /// Enables the timing measurements using the provided timer.
+    ///
+    /// With the `instrumentation` feature `read_frames` records its timing and with the `defmt`
+    /// feature the break and MAB durations of every transmitted package get traced.
+    #[cfg(any(feature = "defmt", feature = "instrumentation"))]
+    pub fn enable_timing_measurements(&mut self, timer: rp2040_hal::Timer) {
+        self.measurement_timer = Some(timer);
+    }
+
+    /// Uses the provided pin for switching the transceiver between receiving (low) and
+    /// transmitting (high). The pin is driven low right away and only driven high while writing.
+    ///
+    /// This is only needed for boards that don't switch the direction automatically.
+    pub fn enable_direction_pin(
+        &mut self,
+        direction_pin: &'a mut dyn OutputPin<Error = Infallible>,
+        config: DirectionPinConfig,
+    ) {
+        direction_pin.set_low().unwrap();
+
+        self.direction_pin = Some(direction_pin);
+        self.direction_pin_config = config;
+        self.transmitter_enabled = false;
+    }
+
+    /// Writes a break, the MAB and the already assembled `frame`, which has to start with the
+    /// start code.
+    ///
+    /// Prefer `send_dmx_package` and `send_custom_package`, which take the slots and the start
+    /// code separately. This is the same as [DmxRespUartDriver::write_frames].
+    pub fn write_raw_frame(
+        &mut self,
+        frame: &[u8],
+    ) -> Result<usize, DmxUartDriverError<Rp2040DriverError>> {
+        self.write_frames(frame)
+    }
+
+    /// Reads back to back frames (start code followed by the slots) into `storage` until it
+    /// is full or no frame arrives within `timeout_us`. Returns the amount of frames stored.
+    ///
+    /// Nothing else happens between the frames, so this can be used to capture a burst of
+    /// frames for analysis.
+    pub fn capture_frames<const N: usize>(
+        &mut self,
+        storage: &mut heapless::Vec<heapless::Vec<u8, DMX_MAX_PACKAGE_SIZE>, N>,
+        timeout_us: u32,
+    ) -> Result<usize, DmxUartDriverError<Rp2040DriverError>> {
+        let mut frames_captured = 0;
+        let mut frame = heapless::Vec::new();
+
+        while !storage.is_full() {
+            frame.resize_default(DMX_MAX_PACKAGE_SIZE).ok();
+
+            let bytes_read = match self.read_frames(&mut frame, timeout_us) {
+                Ok(bytes_read) => bytes_read,
+                Err(DmxUartDriverError::TimeoutError) => break,
+                Err(error) => return Err(error),
+            };
+
+            frame.truncate(bytes_read);
+            // Can't fail, since the storage isn't full.
+            storage.push(core::mem::take(&mut frame)).ok();
+            frames_captured += 1;
+        }
+
+        Ok(frames_captured)
+    }
+
+    /// Same as [DmxRecvUartDriver::read_frames] but waits for a frame without a timeout.
+    /// See [NO_TIMEOUT].
+    pub fn read_frames_blocking(
+        &mut self,
+        buffer: &mut [u8],
+    ) -> Result<usize, DmxUartDriverError<Rp2040DriverError>> {
+        self.read_frames(buffer, NO_TIMEOUT)
+    }
+
+    /// Same as [DmxRecvUartDriver::read_frames] but doesn't fail on parity, framing and overrun
+    /// errors. They are counted instead and the frame is read to its end, which is useful for
+    /// monitoring the error rate of a line.
+    ///
+    /// Slots with a parity or framing error are stored as `0`, so the following slots keep
+    /// their position. Slots lost to an overrun can't be recovered though.
+    pub fn read_frames_counting_errors(
         &mut self,
         buffer: &mut [u8],
         timeout_us: u32,
-    ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
-        self.countdown.start(timeout_us.micros());
+    ) -> Result<(usize, ReadErrorCounts), DmxUartDriverError<Rp2040DriverError>> {
+        let mut error_counts = ReadErrorCounts::default();
+        let read_bytes = self.read_frame(buffer, timeout_us, Some(&mut error_counts))?;
+
+        Ok((read_bytes, error_counts))
+    }
+
+    /// Reads a dmx frame and copies the slots starting at `start_address` into `channels`.
+    /// The footprint is the length of `channels`.
+    ///
+    /// Slots that are missing because the received frame is too short are set to zero.
+    /// Returns `false` if the frame doesn't use the null start code, in which case `channels`
+    /// stays untouched.
+    ///
+    /// Returns [Rp2040DriverError::InvalidChannelRange] without reading if `start_address` is
+    /// not between 1 and 512 or the footprint exceeds slot 512.
+    pub fn read_dmx_channels(
+        &mut self,
+        start_address: u16,
+        channels: &mut [u8],
+        timeout_us: u32,
+    ) -> Result<bool, DmxUartDriverError<Rp2040DriverError>> {
+        if !(1..=512).contains(&start_address)
+            || start_address as usize + channels.len() > DMX_MAX_PACKAGE_SIZE
+        {
+            return Err(DmxUartDriverError::DriverError(
+                Rp2040DriverError::InvalidChannelRange,
+            ));
+        }
+
+        let mut frame = [0u8; DMX_MAX_PACKAGE_SIZE];
+        let bytes_read = self.read_frames(&mut frame, timeout_us)?;
+
+        if bytes_read == 0 || frame[0] != DMX_NULL_START {
+            return Ok(false);
+        }
+
+        let received_channels = frame[1..bytes_read]
+            .get(start_address as usize - 1..)
+            .unwrap_or_default();
+        let channels_available = received_channels.len().min(channels.len());
+
+        channels[..channels_available].copy_from_slice(&received_channels[..channels_available]);
+        channels[channels_available..].fill(0);
+
+        Ok(true)
+    }
+
+    /// Uses the rdm response timeout instead of `timeout_us` for the first read after an rdm
+    /// request was sent.
+    fn read_timeout_us(&mut self, timeout_us: u32) -> u32 {
+        if core::mem::take(&mut self.rdm_response_pending) {
+            return self.config.rdm_response_timeout_us;
+        }
+
+        timeout_us
+    }
+
+    /// Discards everything in the receive fifo, so a response can't be mixed up with bytes
+    /// that arrived before the request was sent.
+    fn discard_receive_fifo(&mut self) {
+        let mut discard_buffer = [0u8; 32];
+
+        self.break_pending = false;
+        while !matches!(
+            self.uart.read_raw(&mut discard_buffer),
+            Err(nb::Error::WouldBlock)
+        ) {}
+    }
+
+    fn wait_for_break(
+        &mut self,
+        timeout_us: u32,
+    ) -> Result<(), DmxUartDriverError<Rp2040DriverError>> {
+        // Reading byte by byte, so a break can't hide in the middle of a chunk.
+        let mut discard_buffer = [0u8; 1];
+
+        self.countdown.start_us(timeout_us);
         loop {
-            match self.uart.read_raw(&mut buffer[0..1]) {
-                Ok(_) => {
-                    // is this really the best way to clear the buffer?
-                    continue;
-                }
-                Err(error) => match error {
-                    nb::Error::Other(ReadError {
-                        err_type: ReadErrorType::Break,
-                        ..
-                    }) => {
-                        break;
-                    }
-                    nb::Error::WouldBlock => {
-                        if self.countdown.wait() != Err(nb::Error::WouldBlock) {
-                            return Err(DmxUartDriverError::TimeoutError);
-                        }
+            match self.uart.read_raw(&mut discard_buffer) {
+                Err(nb::Error::Other(ReadError {
+                    err_type: ReadErrorType::Break,
+                    ..
+                })) => break,
+                Err(nb::Error::WouldBlock) => {
+                    if timeout_us != NO_TIMEOUT
+                        && self.countdown.wait() != Err(nb::Error::WouldBlock)
+                    {
+                        return Err(DmxUartDriverError::TimeoutError);
                     }
-                    _ => continue,
-                },
+                }
+                // Slots and errors of the previous frame are of no interest.
+                Ok(_) | Err(nb::Error::Other(_)) => continue,
             }
         }
 
-        self.countdown.cancel().unwrap();
-        let read_bytes = self.read_frames_no_break(buffer, timeout_us)?;
+        self.countdown.cancel().ok();
+
+        Ok(())
+    }
+
+    fn enable_transmitter(&mut self) {
+        if self.transmitter_enabled {
+            return;
+        }
+
+        let direction_pin = match self.direction_pin {
+            Some(ref mut direction_pin) => direction_pin,
+            None => return,
+        };
+
+        direction_pin.set_high().unwrap();
+        self.transmitter_enabled = true;
+
+        let setup_time_us = self.direction_pin_config.tx_enable_setup_us;
+        self.delay_us(setup_time_us);
+    }
+
+    fn disable_transmitter(&mut self) {
+        if !self.transmitter_enabled {
+            return;
+        }
+
+        let hold_time_us = self.direction_pin_config.tx_disable_hold_us;
+        self.delay_us(hold_time_us);
+
+        if let Some(ref mut direction_pin) = self.direction_pin {
+            direction_pin.set_low().unwrap();
+        }
+        self.transmitter_enabled = false;
+    }
+
+    /// Starts the minimum time a responder has to wait after a request before responding.
+    fn begin_response_turnaround(&mut self) {
+        if !self.responder_mode {
+            return;
+        }
+
+        self.response_turnaround_pending = true;
+        self.start_turnaround(RESPONDER_TURNAROUND_US);
+    }
+
+    /// Waits for the rest of the responder turnaround. Without a turnaround countdown the
+    /// full turnaround is waited, which still leaves enough time until the response is due.
+    fn finish_response_turnaround(&mut self) {
+        if !core::mem::take(&mut self.response_turnaround_pending) {
+            return;
+        }
+
+        match self.turnaround_countdown {
+            Some(_) => self.wait_turnaround(),
+            None => self.delay_us(RESPONDER_TURNAROUND_US),
+        }
+    }
+
+    fn delay_us(&mut self, delay_us: u32) {
+        if delay_us == 0 {
+            return;
+        }
+
+        self.countdown.start_us(delay_us);
+        while self.countdown.wait() == Err(nb::Error::WouldBlock) {}
+        self.countdown.cancel().ok();
+    }
+
+    /// Waits for a break and reads the frame that follows it. Errors are counted in
+    /// `error_counts` instead of being returned if it is set.
+    fn read_frame(
+        &mut self,
+        buffer: &mut [u8],
+        timeout_us: u32,
+        error_counts: Option<&mut ReadErrorCounts>,
+    ) -> Result<usize, DmxUartDriverError<Rp2040DriverError>> {
+        self.discovery_response_pending = false;
+        let timeout_us = self.read_timeout_us(timeout_us);
+
+        // A break that ended the previous read already belongs to the frame that follows it.
+        if !core::mem::take(&mut self.break_pending) {
+            self.wait_for_break(timeout_us)?;
+        }
+
+        #[cfg(feature = "instrumentation")]
+        self.instrument_break();
+
+        let read_bytes = match self.read_slots(buffer, timeout_us, true, error_counts) {
+            // No slot followed the break, so this is a break-only frame as well.
+            Err(DmxUartDriverError::TimeoutError) => 0,
+            result => result?,
+        };
+        self.begin_response_turnaround();
 
         Ok(read_bytes)
     }
 
-    fn read_frames_no_break(
+    /// Reads slots until the frame ends. If `after_break` is set, a break before the first slot
+    /// ends the frame as an empty one, otherwise it is skipped.
+    fn read_slots(
         &mut self,
         buffer: &mut [u8],
         timeout_us: u32,
-    ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
-        const MAXIMUM_MAB_TIME_MS: u32 = 1;
+        after_break: bool,
+        mut error_counts: Option<&mut ReadErrorCounts>,
+    ) -> Result<usize, DmxUartDriverError<Rp2040DriverError>> {
+        const MAXIMUM_MAB_TIME_US: u32 = 1_000;
 
         let buffer_size = buffer.len();
         let mut head = 0;
 
-        self.countdown.start(timeout_us.micros());
+        let timeout_us = self.read_timeout_us(timeout_us);
+        self.countdown.start_us(timeout_us);
+        // Only the wait for the first slot can be endless, the frame still ends after a gap.
+        let mut wait_forever = timeout_us == NO_TIMEOUT;
 
         while head < buffer_size {
             let bytes_read = match self.uart.read_raw(&mut buffer[head..buffer_size]) {
                 Ok(bytes_read) => {
-                    self.countdown.start(MAXIMUM_MAB_TIME_MS.millis());
+                    wait_forever = false;
+                    self.countdown.start_us(MAXIMUM_MAB_TIME_US);
+                    #[cfg(feature = "instrumentation")]
+                    self.instrument_slots(bytes_read);
 
                     Ok(bytes_read)
                 }
                 Err(err) => match err {
-                    nb::Error::Other(ref read_error) => match read_error.err_type {
+                    nb::Error::Other(read_error) => match read_error.err_type {
                         ReadErrorType::Break => {
-                            if head == 0 {
+                            // The bytes in front of the break are the end of this frame.
+                            let bytes_before_break = read_error.discarded.len();
+                            // Right after a break this is a break-only frame, which is valid.
+                            if head == 0 && bytes_before_break == 0 && !after_break {
                                 continue;
                             }
 
+                            #[cfg(feature = "instrumentation")]
+                            self.instrument_slots(bytes_before_break);
+
+                            head += bytes_before_break;
+                            self.break_pending = true;
                             break;
                         }
-                        ReadErrorType::Overrun => {
-                            Err(DmxUartDriverError::DriverError(Rp2040DriverError::Overflow))
+                        // Colliding discovery responses show up as garbage. Returning what was read
+                        // lets the discovery detect the collision instead of failing.
+                        ReadErrorType::Parity | ReadErrorType::Framing
+                            if self.discovery_response_pending =>
+                        {
+                            self.discovery_response_pending = false;
+                            return Ok(head + read_error.discarded.len());
                         }
-                        ReadErrorType::Parity => {
-                            Err(DmxUartDriverError::DriverError(Rp2040DriverError::Parity))
+                        _ if error_counts.is_some() => {
+                            wait_forever = false;
+                            self.countdown.start_us(MAXIMUM_MAB_TIME_US);
+                            // The bytes in front of the error are valid.
+                            head += read_error.discarded.len();
+
+                            let slot_dropped =
+                                error_counts.as_deref_mut().is_some_and(|error_counts| {
+                                    error_counts.record(&read_error.err_type)
+                                });
+
+                            // The hal drops the faulty slot, a placeholder keeps the position of
+                            // the following slots.
+                            if slot_dropped && head < buffer_size {
+                                buffer[head] = 0;
+                                head += 1;
+                            }
+
+                            continue;
                         }
-                        ReadErrorType::Framing => {
-                            Err(DmxUartDriverError::DriverError(Rp2040DriverError::Framing))
+                        ReadErrorType::Overrun => {
+                            // The error only holds the bytes of the current read, the frame
+                            // also loses the slots that were already in the buffer.
+                            Err(DmxUartDriverError::DriverError(
+                                Rp2040DriverError::Overflow {
+                                    discarded: head + read_error.discarded.len(),
+                                },
+                            ))
                         }
+                        _ => Err(DmxUartDriverError::DriverError(read_error.into())),
                     },
                     nb::Error::WouldBlock => {
-                        if self.countdown.wait() != Err(nb::Error::WouldBlock) {
+                        if !wait_forever && self.countdown.wait() != Err(nb::Error::WouldBlock) {
                             if head == 0 {
                                 return Err(DmxUartDriverError::TimeoutError);
                             }
@@ -154,18 +740,134 @@ impl<D: UartDevice, P: ValidUartPinout<D>> DmxRecvUartDriver for Rp2040Driver<'_
             head += bytes_read;
         }
 
-        self.countdown.cancel().unwrap();
+        self.countdown.cancel().ok();
 
         Ok(head)
     }
+
+    fn begin_package(&mut self) {
+        #[cfg(feature = "defmt")]
+        let break_start = self.measurement_ticks();
+        self.uart.lowlevel_break_start();
+
+        self.countdown.start_us(200); // BRK
+        while self.countdown.wait() == Err(nb::Error::WouldBlock) {}
+        self.uart.lowlevel_break_stop();
+
+        #[cfg(feature = "defmt")]
+        let mab_start = self.measurement_ticks();
+
+        self.countdown.start_us(48); // MAB
+        while self.countdown.wait() == Err(nb::Error::WouldBlock) {}
+
+        #[cfg(feature = "defmt")]
+        if let (Some(break_start), Some(mab_start), Some(mab_end)) =
+            (break_start, mab_start, self.measurement_ticks())
+        {
+            defmt::trace!(
+                "BRK: {}µs, MAB: {}µs",
+                mab_start.wrapping_sub(break_start),
+                mab_end.wrapping_sub(mab_start)
+            );
+        }
+    }
+
+    #[cfg(feature = "defmt")]
+    fn measurement_ticks(&self) -> Option<u64> {
+        self.measurement_timer
+            .map(|measurement_timer| measurement_timer.get_counter().ticks())
+    }
 }
 
-impl<D: UartDevice, P: ValidUartPinout<D>> DmxRespUartDriver for Rp2040Driver<'_, D, P> {
+impl<U: Rp2040Uart, C: Rp2040CountDown> DmxUartDriver for Rp2040Driver<'_, U, C> {
+    type DriverError = Rp2040DriverError;
+}
+
+impl<U: Rp2040Uart, C: Rp2040CountDown> DmxRecvUartDriver for Rp2040Driver<'_, U, C> {
+    /// Waits for a break and reads the frame that follows it.
+    ///
+    /// A break that is directly followed by the next break or by no slots within `timeout_us`
+    /// is an empty frame and returns `Ok(0)`. The next call then reads the frame that follows.
+    fn read_frames(
+        &mut self,
+        buffer: &mut [u8],
+        timeout_us: u32,
+    ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+        self.read_frame(buffer, timeout_us, None)
+    }
+
+    fn read_frames_no_break(
+        &mut self,
+        buffer: &mut [u8],
+        timeout_us: u32,
+    ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+        let read_bytes = self.read_slots(buffer, timeout_us, false, None)?;
+        self.begin_response_turnaround();
+
+        Ok(read_bytes)
+    }
+}
+
+impl<U: Rp2040Uart, C: Rp2040CountDown> DmxRespUartDriver for Rp2040Driver<'_, U, C> {
+    /// Writes a break, the MAB and the `buffer` (start code followed by the slots).
+    ///
+    /// The frame ends after the last byte of `buffer`, so passing fewer than 512 slots produces
+    /// a valid short frame. Short frames increase the refresh rate for small rigs
+    /// (a frame with 24 slots takes about 1.4ms instead of 23ms for a full universe).
+    ///
+    /// Returns [Rp2040DriverError::EmptyFrame] if `buffer` doesn't even contain a start code.
+    /// Frames with more than 512 slots are handled according to
+    /// [Rp2040DriverConfig::slot_count_validation].
     fn write_frames(
         &mut self,
         buffer: &[u8],
     ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
-        self.begin_package();
+        if buffer.is_empty() {
+            return Err(DmxUartDriverError::DriverError(
+                Rp2040DriverError::EmptyFrame,
+            ));
+        }
+
+        let buffer = match buffer.len() > DMX_MAX_PACKAGE_SIZE {
+            true => match self.config.slot_count_validation {
+                SlotCountValidation::Strict => {
+                    return Err(DmxUartDriverError::DriverError(
+                        Rp2040DriverError::FrameTooLong,
+                    ))
+                }
+                SlotCountValidation::Lenient => {
+                    #[cfg(feature = "defmt")]
+                    defmt::warn!(
+                        "Frame has {} slots, truncating it to 512.",
+                        buffer.len() - 1
+                    );
+
+                    &buffer[..DMX_MAX_PACKAGE_SIZE]
+                }
+            },
+            false => buffer,
+        };
+
+        self.finish_response_turnaround();
+
+        // Anything received up to now can't be the response to this package.
+        self.discard_receive_fifo();
+        self.discovery_response_pending = is_discovery_request(buffer);
+        self.rdm_response_pending = is_rdm_request(buffer);
+
+        // The break has to be on the line already, so the transmitter is enabled before it.
+        self.enable_transmitter();
+
+        let breaks = match self.session_started {
+            true => 1,
+            false => self.config.startup_breaks.max(1),
+        };
+        self.session_started = true;
+
+        for _ in 0..breaks {
+            self.begin_package();
+        }
+
         self.write_frames_no_break(buffer)
     }
 
@@ -173,8 +875,359 @@ impl<D: UartDevice, P: ValidUartPinout<D>> DmxRespUartDriver for Rp2040Driver<'_
         &mut self,
         buffer: &[u8],
     ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+        // Discovery responses are sent without a break.
+        self.finish_response_turnaround();
+
+        self.enable_transmitter();
         self.uart.write_full_blocking(buffer);
         while self.uart.uart_is_busy() {}
+        self.disable_transmitter();
+
         Ok(buffer.len())
     }
 }
+
+/// Checks if the frame is an rdm request that expects a response.
+///
+/// Broadcast and vendorcast requests aren't answered, except for DISC_UNIQUE_BRANCH, which
+/// every un-muted device answers.
+fn is_rdm_request(frame: &[u8]) -> bool {
+    const REQUEST_COMMAND_CLASSES: [u8; 3] = [0x10, 0x20, 0x30];
+    // The device id of the destination uid, which is 0xFFFFFFFF for broadcasts and vendorcasts.
+    const BROADCAST_DEVICE_ID: [u8; 4] = [0xFF; 4];
+
+    frame.len() > 20
+        && frame[0] == SC_RDM
+        && REQUEST_COMMAND_CLASSES.contains(&frame[20])
+        && (frame[5..9] != BROADCAST_DEVICE_ID || is_discovery_request(frame))
+}
+
+/// Checks if the frame is an rdm DISC_UNIQUE_BRANCH request, which is answered without a break.
+fn is_discovery_request(frame: &[u8]) -> bool {
+    const DISCOVERY_COMMAND: u8 = 0x10;
+    const DISC_UNIQUE_BRANCH: [u8; 2] = [0x00, 0x01];
+
+    frame.len() > 23
+        && frame[0] == SC_RDM
+        && frame[20] == DISCOVERY_COMMAND
+        && frame[21..23] == DISC_UNIQUE_BRANCH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockCountDown, MockEvent, MockUart};
+
+    const TIMEOUT_US: u32 = 1_000;
+
+    fn mock_driver<'a>(
+        events: &'a [MockEvent],
+        write_buffer: &'a mut [u8],
+    ) -> Rp2040Driver<'a, MockUart<'a>, MockCountDown> {
+        Rp2040Driver::new(MockUart::new(events, write_buffer), MockCountDown::new(3))
+    }
+
+    #[test]
+    fn back_to_back_frames_are_split_on_the_break() {
+        use MockEvent::*;
+        let events = [Break, Byte(0), Byte(1), Break, Byte(0), Byte(9), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        let bytes_read = driver.read_frames(&mut buffer, TIMEOUT_US).unwrap();
+        assert_eq!(&buffer[..bytes_read], &[0, 1]);
+
+        let bytes_read = driver.read_frames(&mut buffer, TIMEOUT_US).unwrap();
+        assert_eq!(&buffer[..bytes_read], &[0, 9]);
+    }
+
+    #[test]
+    fn read_times_out_without_break() {
+        let events = [MockEvent::Byte(0), MockEvent::Byte(1), MockEvent::Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert!(matches!(
+            driver.read_frames(&mut buffer, TIMEOUT_US),
+            Err(DmxUartDriverError::TimeoutError)
+        ));
+    }
+
+    #[test]
+    fn break_only_frame_is_empty() {
+        use MockEvent::*;
+        let events = [Break, Break, Byte(0), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert_eq!(driver.read_frames(&mut buffer, TIMEOUT_US).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_without_break_returns_the_slots() {
+        use MockEvent::*;
+        let events = [Byte(0xFE), Byte(0xAA), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        let bytes_read = driver
+            .read_frames_no_break(&mut buffer, TIMEOUT_US)
+            .unwrap();
+        assert_eq!(&buffer[..bytes_read], &[0xFE, 0xAA]);
+    }
+
+    #[test]
+    fn overrun_is_reported_with_the_discarded_slots() {
+        use MockEvent::*;
+        // The idle splits the frame into two reads, the overrun only happens in the second.
+        let events = [Break, Byte(0), Byte(1), Idle, Byte(2), Overrun, Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert!(matches!(
+            driver.read_frames(&mut buffer, TIMEOUT_US),
+            Err(DmxUartDriverError::DriverError(
+                Rp2040DriverError::Overflow { discarded: 3 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn framing_error_fails_the_read() {
+        use MockEvent::*;
+        let events = [Break, Byte(0), FramingError, Byte(1), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert!(matches!(
+            driver.read_frames(&mut buffer, TIMEOUT_US),
+            Err(DmxUartDriverError::DriverError(Rp2040DriverError::Framing))
+        ));
+    }
+
+    #[test]
+    fn write_returns_the_amount_of_bytes_written() {
+        let frame = [0, 10, 20, 30];
+        let mut write_buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+        let mut driver = mock_driver(&[], &mut write_buffer);
+
+        assert_eq!(driver.write_frames(&frame).unwrap(), frame.len());
+        assert_eq!(driver.uart.written(), &frame);
+        assert_eq!(driver.uart.breaks_sent(), 1);
+    }
+
+    #[test]
+    fn write_rejects_empty_frame() {
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&[], &mut write_buffer);
+
+        assert!(matches!(
+            driver.write_frames(&[]),
+            Err(DmxUartDriverError::DriverError(
+                Rp2040DriverError::EmptyFrame
+            ))
+        ));
+    }
+
+    #[test]
+    fn write_rejects_frame_with_more_than_512_slots() {
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&[], &mut write_buffer);
+
+        assert!(matches!(
+            driver.write_frames(&[0; DMX_MAX_PACKAGE_SIZE + 1]),
+            Err(DmxUartDriverError::DriverError(
+                Rp2040DriverError::FrameTooLong
+            ))
+        ));
+        assert_eq!(driver.uart.breaks_sent(), 0);
+    }
+
+    #[test]
+    fn lenient_write_truncates_frame_to_512_slots() {
+        let mut frame = [0x7F; DMX_MAX_PACKAGE_SIZE + 1];
+        frame[0] = DMX_NULL_START;
+        let mut write_buffer = [0u8; DMX_MAX_PACKAGE_SIZE + 1];
+        let config = Rp2040DriverConfig {
+            slot_count_validation: SlotCountValidation::Lenient,
+            ..Rp2040DriverConfig::default()
+        };
+        let mut driver = Rp2040Driver::new_with_config(
+            MockUart::new(&[], &mut write_buffer),
+            MockCountDown::new(3),
+            config,
+        );
+
+        assert_eq!(driver.write_frames(&frame).unwrap(), DMX_MAX_PACKAGE_SIZE);
+        assert_eq!(driver.uart.written(), &frame[..DMX_MAX_PACKAGE_SIZE]);
+        assert_eq!(driver.uart.breaks_sent(), 1);
+    }
+
+    #[test]
+    fn turnaround_countdown_can_be_combined_with_a_config() {
+        let mut write_buffer = [];
+        let config = Rp2040DriverConfig {
+            startup_breaks: 3,
+            ..Rp2040DriverConfig::default()
+        };
+        let mut driver = Rp2040Driver::new_with_config(
+            MockUart::new(&[], &mut write_buffer),
+            MockCountDown::new(3),
+            config,
+        )
+        .with_turnaround_countdown(MockCountDown::new(3));
+
+        assert_eq!(driver.config.startup_breaks, 3);
+        assert!(driver.start_turnaround(RESPONDER_TURNAROUND_US));
+    }
+
+    #[test]
+    fn break_followed_by_break_is_empty_frame() {
+        use MockEvent::*;
+        let events = [Break, Break, Byte(0), Byte(2), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert_eq!(driver.read_frames(&mut buffer, TIMEOUT_US).unwrap(), 0);
+
+        let bytes_read = driver.read_frames(&mut buffer, TIMEOUT_US).unwrap();
+        assert_eq!(&buffer[..bytes_read], &[0, 2]);
+    }
+
+    #[test]
+    fn break_followed_by_idle_is_empty_frame() {
+        use MockEvent::*;
+        // Enough idle polls for the countdown to elapse before the next break arrives.
+        let events = [Break, Idle, Idle, Idle, Idle, Break, Byte(0), Byte(2), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        assert_eq!(driver.read_frames(&mut buffer, TIMEOUT_US).unwrap(), 0);
+
+        let bytes_read = driver.read_frames(&mut buffer, TIMEOUT_US).unwrap();
+        assert_eq!(&buffer[..bytes_read], &[0, 2]);
+    }
+
+    #[test]
+    fn no_timeout_waits_for_the_frame() {
+        use MockEvent::*;
+        let mut events = [Idle; 32];
+        events[10] = Break;
+        events[28..].copy_from_slice(&[Byte(0), Byte(5), Byte(6), Idle]);
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        let bytes_read = driver.read_frames_blocking(&mut buffer).unwrap();
+        assert_eq!(&buffer[..bytes_read], &[0, 5, 6]);
+    }
+
+    #[test]
+    fn counting_errors_reads_the_frame_to_its_end() {
+        use MockEvent::*;
+        let events = [
+            Break,
+            Byte(0),
+            ParityError,
+            Byte(2),
+            Overrun,
+            Byte(3),
+            Break,
+            Idle,
+        ];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut buffer = [0u8; DMX_MAX_PACKAGE_SIZE];
+
+        let (bytes_read, error_counts) = driver
+            .read_frames_counting_errors(&mut buffer, TIMEOUT_US)
+            .unwrap();
+
+        // The placeholder keeps the position of the slot after the parity error.
+        assert_eq!(&buffer[..bytes_read], &[0, 0, 2, 3]);
+        // The break that ends the frame isn't counted.
+        assert_eq!(
+            error_counts,
+            ReadErrorCounts {
+                parity: 1,
+                framing: 0,
+                overrun: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn read_dmx_channels_copies_the_footprint() {
+        use MockEvent::*;
+        let events = [Break, Byte(0), Byte(1), Byte(2), Byte(3), Idle];
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&events, &mut write_buffer);
+        let mut channels = [0xFF; 3];
+
+        assert!(driver
+            .read_dmx_channels(2, &mut channels, TIMEOUT_US)
+            .unwrap());
+        // The third channel is missing from the short frame.
+        assert_eq!(channels, [2, 3, 0]);
+    }
+
+    #[test]
+    fn read_dmx_channels_rejects_invalid_range() {
+        let mut write_buffer = [];
+        let mut driver = mock_driver(&[], &mut write_buffer);
+
+        for (start_address, footprint) in [(0, 1), (513, 1), (512, 2)] {
+            let mut channels = [0u8; 2];
+            assert!(matches!(
+                driver.read_dmx_channels(start_address, &mut channels[..footprint], TIMEOUT_US),
+                Err(DmxUartDriverError::DriverError(
+                    Rp2040DriverError::InvalidChannelRange
+                ))
+            ));
+        }
+    }
+
+    fn rdm_request(destination_uid: [u8; 6], command_class: u8, parameter_id: u16) -> [u8; 26] {
+        let mut request = [0u8; 26];
+        request[..3].copy_from_slice(&[SC_RDM, 0x01, 0x18]);
+        request[3..9].copy_from_slice(&destination_uid);
+        request[9..15].copy_from_slice(&[0x12, 0x34, 0x00, 0x00, 0x00, 0x01]);
+        request[20] = command_class;
+        request[21..23].copy_from_slice(&parameter_id.to_be_bytes());
+
+        request
+    }
+
+    #[test]
+    fn only_answered_requests_wait_for_a_response() {
+        const DEVICE: [u8; 6] = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+        const BROADCAST: [u8; 6] = [0xFF; 6];
+        const VENDORCAST: [u8; 6] = [0x12, 0x34, 0xFF, 0xFF, 0xFF, 0xFF];
+        const GET_DEVICE_INFO: (u8, u16) = (0x20, 0x0060);
+        const SET_IDENTIFY: (u8, u16) = (0x30, 0x1000);
+        const DISC_UNIQUE_BRANCH: (u8, u16) = (0x10, 0x0001);
+
+        for (destination_uid, (command_class, parameter_id), response_expected) in [
+            (DEVICE, GET_DEVICE_INFO, true),
+            (BROADCAST, SET_IDENTIFY, false),
+            (VENDORCAST, SET_IDENTIFY, false),
+            (BROADCAST, DISC_UNIQUE_BRANCH, true),
+        ] {
+            let mut write_buffer = [0u8; 26];
+            let mut driver = mock_driver(&[], &mut write_buffer);
+
+            let request = rdm_request(destination_uid, command_class, parameter_id);
+            driver.write_frames(&request).unwrap();
+
+            assert_eq!(driver.rdm_response_pending, response_expected);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::Rp2040Uart;
+use embedded_hal_0_2::timer::{Cancel, CountDown};
+use fugit::MicrosDurationU32;
+use rp2040_hal::uart::{ReadError, ReadErrorType};
+use void::Void;
+
+/// An event on the receiving side of a [MockUart].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MockEvent {
+    /// A byte was received without errors.
+    Byte(u8),
+    /// A break was received.
+    Break,
+    /// A byte was received with a parity error.
+    ParityError,
+    /// A byte was received with a framing error.
+    FramingError,
+    /// The receive fifo overflowed.
+    Overrun,
+    /// The receive fifo ran empty, so the next read returns `WouldBlock`.
+    Idle,
+}
+
+/// A uart that replays a scripted sequence of received events and records everything written.
+///
+/// Once the script is exhausted every read returns `WouldBlock`.
+pub struct MockUart<'a> {
+    events: &'a [MockEvent],
+    position: usize,
+    written: &'a mut [u8],
+    written_len: usize,
+    breaks_sent: usize,
+}
+
+impl<'a> MockUart<'a> {
+    /// Creates a mock uart that replays `events` and writes into `write_buffer`.
+    ///
+    /// Panics on writes that don't fit into `write_buffer`.
+    pub fn new(events: &'a [MockEvent], write_buffer: &'a mut [u8]) -> Self {
+        Self {
+            events,
+            position: 0,
+            written: write_buffer,
+            written_len: 0,
+            breaks_sent: 0,
+        }
+    }
+
+    /// Returns all bytes that have been written so far.
+    pub fn written(&self) -> &[u8] {
+        &self.written[..self.written_len]
+    }
+
+    /// Returns the number of breaks that have been sent so far.
+    pub fn breaks_sent(&self) -> usize {
+        self.breaks_sent
+    }
+
+    /// Returns the number of events that haven't been read yet.
+    pub fn remaining_events(&self) -> usize {
+        self.events.len() - self.position
+    }
+}
+
+impl Rp2040Uart for MockUart<'_> {
+    // This mirrors the hal: bytes in front of an error end up in `discarded`
+    // and an empty fifo only blocks if nothing has been read yet.
+    fn read_raw<'b>(&mut self, buffer: &'b mut [u8]) -> nb::Result<usize, ReadError<'b>> {
+        let mut bytes_read = 0;
+
+        while bytes_read < buffer.len() {
+            let err_type = match self.events.get(self.position) {
+                None => break,
+                Some(MockEvent::Idle) => {
+                    if bytes_read == 0 {
+                        self.position += 1;
+                    }
+                    break;
+                }
+                Some(MockEvent::Byte(byte)) => {
+                    buffer[bytes_read] = *byte;
+                    bytes_read += 1;
+                    self.position += 1;
+                    continue;
+                }
+                Some(MockEvent::Break) => ReadErrorType::Break,
+                Some(MockEvent::ParityError) => ReadErrorType::Parity,
+                Some(MockEvent::FramingError) => ReadErrorType::Framing,
+                Some(MockEvent::Overrun) => ReadErrorType::Overrun,
+            };
+
+            self.position += 1;
+            return Err(nb::Error::Other(ReadError {
+                err_type,
+                discarded: &buffer[..bytes_read],
+            }));
+        }
+
+        if bytes_read == 0 && !buffer.is_empty() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(bytes_read)
+    }
+
+    fn write_full_blocking(&mut self, data: &[u8]) {
+        self.written[self.written_len..self.written_len + data.len()].copy_from_slice(data);
+        self.written_len += data.len();
+    }
+
+    fn uart_is_busy(&self) -> bool {
+        false
+    }
+
+    fn uart_is_readable(&self) -> bool {
+        !matches!(self.events.get(self.position), None | Some(MockEvent::Idle))
+    }
+
+    fn lowlevel_break_start(&mut self) {
+        self.breaks_sent += 1;
+    }
+
+    fn lowlevel_break_stop(&mut self) {}
+}
+
+/// A countdown that elapses after being polled a fixed number of times instead of using a
+/// clock. Like the countdown of the hal it restarts automatically once it elapsed.
+pub struct MockCountDown {
+    polls_until_elapsed: u32,
+    remaining_polls: Option<u32>,
+}
+
+impl MockCountDown {
+    /// Creates a countdown that elapses on the `polls_until_elapsed`th call to `wait`
+    /// after being started, regardless of the requested timeout.
+    pub fn new(polls_until_elapsed: u32) -> Self {
+        Self {
+            polls_until_elapsed,
+            remaining_polls: None,
+        }
+    }
+}
+
+impl CountDown for MockCountDown {
+    type Time = MicrosDurationU32;
+
+    fn start<T>(&mut self, _count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.remaining_polls = Some(self.polls_until_elapsed);
+    }
+
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        let remaining_polls = match self.remaining_polls {
+            Some(ref mut remaining_polls) => remaining_polls,
+            None => return Err(nb::Error::WouldBlock),
+        };
+
+        if *remaining_polls > 1 {
+            *remaining_polls -= 1;
+            return Err(nb::Error::WouldBlock);
+        }
+
+        *remaining_polls = self.polls_until_elapsed;
+        Ok(())
+    }
+}
+
+impl Cancel for MockCountDown {
+    type Error = &'static str;
+
+    fn cancel(&mut self) -> Result<(), Self::Error> {
+        match self.remaining_polls.take() {
+            Some(_) => Ok(()),
+            None => Err("CountDown is not running."),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{Rp2040CountDown, Rp2040Driver, Rp2040DriverError, Rp2040Uart};
+use dmx_rdm::dmx_receiver::{DmxResponderHandler, PollingError, RdmResponder};
+use dmx_rdm::rdm_responder::RdmResponderConfig;
+
+/// An rdm responder running on the [Rp2040Driver].
+///
+/// Discovery, mute and un-mute requests are answered by the responder, every other rdm
+/// request and all dmx frames are passed to the handler. The driver waits for the minimum
+/// turnaround of 176µs before sending a response, so responding right away is fine.
+/// A driver with a turnaround countdown (see [Rp2040Driver::with_turnaround_countdown]) only
+/// waits for the part of the turnaround that hasn't already passed while handling the request.
+pub struct Rp2040Responder<'a, U: Rp2040Uart, C: Rp2040CountDown, const MQ_SIZE: usize> {
+    responder: RdmResponder<Rp2040Driver<'a, U, C>, MQ_SIZE>,
+}
+
+impl<'a, U: Rp2040Uart, C: Rp2040CountDown, const MQ_SIZE: usize>
+    Rp2040Responder<'a, U, C, MQ_SIZE>
+{
+    pub fn new(mut driver: Rp2040Driver<'a, U, C>, config: RdmResponderConfig) -> Self {
+        driver.responder_mode = true;
+
+        Self {
+            responder: RdmResponder::new(driver, config),
+        }
+    }
+
+    /// Receives a frame and handles it. Call this in the main loop as often as possible.
+    ///
+    /// Returns `false` if no frame was received.
+    pub fn poll<HandlerError>(
+        &mut self,
+        handler: &mut dyn DmxResponderHandler<Error = HandlerError>,
+    ) -> Result<bool, PollingError<Rp2040DriverError, HandlerError>> {
+        self.responder.poll(handler)
+    }
+
+    /// Get a reference to the underlying responder, e.g. for accessing the message queue.
+    pub fn get_responder(&mut self) -> &mut RdmResponder<Rp2040Driver<'a, U, C>, MQ_SIZE> {
+        &mut self.responder
+    }
+}
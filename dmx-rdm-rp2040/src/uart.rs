@@ -0,0 +1,59 @@
+use rp2040_hal::uart::{
+    Enabled, FifoWatermark, ReadError, UartDevice, UartPeripheral, ValidUartPinout,
+};
+
+/// The parts of the uart the driver uses. This is implemented for the enabled
+/// [UartPeripheral] of the hal and allows replacing the uart for testing.
+pub trait Rp2040Uart {
+    /// Reads the available bytes. See [UartPeripheral::read_raw].
+    fn read_raw<'b>(&mut self, buffer: &'b mut [u8]) -> nb::Result<usize, ReadError<'b>>;
+
+    /// Writes all bytes to the transmit fifo. See [UartPeripheral::write_full_blocking].
+    fn write_full_blocking(&mut self, data: &[u8]);
+
+    /// Returns `true` while the uart is still transmitting.
+    fn uart_is_busy(&self) -> bool;
+
+    /// Returns `true` if the receive fifo isn't empty.
+    fn uart_is_readable(&self) -> bool;
+
+    /// Starts driving the line low.
+    fn lowlevel_break_start(&mut self);
+
+    /// Stops driving the line low.
+    fn lowlevel_break_stop(&mut self);
+
+    /// Sets the fill level of the receive fifo that triggers the rx interrupt.
+    /// See [UartPeripheral::set_rx_watermark]. Uarts without a fifo can ignore this.
+    fn set_rx_watermark(&mut self, _watermark: FifoWatermark) {}
+}
+
+impl<D: UartDevice, P: ValidUartPinout<D>> Rp2040Uart for UartPeripheral<Enabled, D, P> {
+    fn read_raw<'b>(&mut self, buffer: &'b mut [u8]) -> nb::Result<usize, ReadError<'b>> {
+        UartPeripheral::read_raw(self, buffer)
+    }
+
+    fn write_full_blocking(&mut self, data: &[u8]) {
+        UartPeripheral::write_full_blocking(self, data)
+    }
+
+    fn uart_is_busy(&self) -> bool {
+        UartPeripheral::uart_is_busy(self)
+    }
+
+    fn uart_is_readable(&self) -> bool {
+        UartPeripheral::uart_is_readable(self)
+    }
+
+    fn lowlevel_break_start(&mut self) {
+        UartPeripheral::lowlevel_break_start(self)
+    }
+
+    fn lowlevel_break_stop(&mut self) {
+        UartPeripheral::lowlevel_break_stop(self)
+    }
+
+    fn set_rx_watermark(&mut self, watermark: FifoWatermark) {
+        UartPeripheral::set_rx_watermark(self, watermark)
+    }
+}
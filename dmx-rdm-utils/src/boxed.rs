@@ -0,0 +1,149 @@
+use dmx_rdm::dmx_driver::{
+    ControllerDriverErrorDef, CustomStartCodeControllerDriver, DiscoveryOption,
+    DmxControllerDriver, DmxError, RdmControllerDriver,
+};
+use dmx_rdm::rdm_data::RdmData;
+use dmx_rdm::unique_identifier::UniqueIdentifier;
+use std::error::Error;
+
+/// The error of a [BoxedController]. It contains the error of the wrapped driver.
+pub type BoxedDriverError = Box<dyn Error + Send + Sync>;
+
+/// Object safe version of the controller driver traits. It is implemented for every driver
+/// whose error implements [Error].
+pub trait ErasedControllerDriver {
+    fn send_dmx_package(&mut self, package: &[u8]) -> Result<(), DmxError<BoxedDriverError>>;
+    fn send_custom_package(
+        &mut self,
+        start_code: u8,
+        package: &[u8],
+    ) -> Result<(), DmxError<BoxedDriverError>>;
+    fn send_rdm(&mut self, package: RdmData) -> Result<(), DmxError<BoxedDriverError>>;
+    fn receive_rdm(&mut self) -> Result<RdmData, DmxError<BoxedDriverError>>;
+    fn receive_rdm_discovery_response(
+        &mut self,
+    ) -> Result<DiscoveryOption, DmxError<BoxedDriverError>>;
+    fn send_rdm_discovery_response(
+        &mut self,
+        uid: UniqueIdentifier,
+    ) -> Result<(), DmxError<BoxedDriverError>>;
+}
+
+impl<D> ErasedControllerDriver for D
+where
+    D: DmxControllerDriver + RdmControllerDriver + CustomStartCodeControllerDriver,
+    D::DriverError: Error + Send + Sync + 'static,
+{
+    fn send_dmx_package(&mut self, package: &[u8]) -> Result<(), DmxError<BoxedDriverError>> {
+        DmxControllerDriver::send_dmx_package(self, package).map_err(erase_error)
+    }
+
+    fn send_custom_package(
+        &mut self,
+        start_code: u8,
+        package: &[u8],
+    ) -> Result<(), DmxError<BoxedDriverError>> {
+        CustomStartCodeControllerDriver::send_custom_package(self, start_code, package)
+            .map_err(erase_error)
+    }
+
+    fn send_rdm(&mut self, package: RdmData) -> Result<(), DmxError<BoxedDriverError>> {
+        RdmControllerDriver::send_rdm(self, package).map_err(erase_error)
+    }
+
+    fn receive_rdm(&mut self) -> Result<RdmData, DmxError<BoxedDriverError>> {
+        RdmControllerDriver::receive_rdm(self).map_err(erase_error)
+    }
+
+    fn receive_rdm_discovery_response(
+        &mut self,
+    ) -> Result<DiscoveryOption, DmxError<BoxedDriverError>> {
+        RdmControllerDriver::receive_rdm_discovery_response(self).map_err(erase_error)
+    }
+
+    fn send_rdm_discovery_response(
+        &mut self,
+        uid: UniqueIdentifier,
+    ) -> Result<(), DmxError<BoxedDriverError>> {
+        RdmControllerDriver::send_rdm_discovery_response(self, uid).map_err(erase_error)
+    }
+}
+
+fn erase_error<E: Error + Send + Sync + 'static>(error: DmxError<E>) -> DmxError<BoxedDriverError> {
+    match error {
+        DmxError::UartOverflow => DmxError::UartOverflow,
+        DmxError::TimeoutError => DmxError::TimeoutError,
+        DmxError::DeserializationError(error) => DmxError::DeserializationError(error),
+        DmxError::DriverError(error) => DmxError::DriverError(Box::new(error)),
+    }
+}
+
+/// A controller driver of any type. This allows picking the driver at runtime, e.g. an Enttec
+/// DMX Pro or a plain ftdi cable depending on the configuration.
+///
+/// ```ignore
+/// let driver = match config.interface {
+///     Interface::EnttecPro => BoxedController::new(EnttecProDriver::new(serial_port)?),
+///     Interface::Ftdi => BoxedController::new(FtdiDriver::new(serial_port, Default::default())?),
+/// };
+/// let dmx_controller = DmxController::new(driver, &DmxControllerConfig { rdm_uid });
+/// ```
+pub struct BoxedController {
+    driver: Box<dyn ErasedControllerDriver + Send>,
+}
+
+impl BoxedController {
+    pub fn new<D: ErasedControllerDriver + Send + 'static>(driver: D) -> Self {
+        Self {
+            driver: Box::new(driver),
+        }
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut (dyn ErasedControllerDriver + Send) {
+        self.driver.as_mut()
+    }
+}
+
+impl ControllerDriverErrorDef for BoxedController {
+    type DriverError = BoxedDriverError;
+}
+
+impl DmxControllerDriver for BoxedController {
+    fn send_dmx_package(&mut self, package: &[u8]) -> Result<(), DmxError<Self::DriverError>> {
+        self.driver.send_dmx_package(package)
+    }
+}
+
+impl CustomStartCodeControllerDriver for BoxedController {
+    fn send_custom_package(
+        &mut self,
+        start_code: u8,
+        package: &[u8],
+    ) -> Result<(), DmxError<Self::DriverError>> {
+        self.driver.send_custom_package(start_code, package)
+    }
+}
+
+impl RdmControllerDriver for BoxedController {
+    fn send_rdm(&mut self, package: RdmData) -> Result<(), DmxError<Self::DriverError>> {
+        self.driver.send_rdm(package)
+    }
+
+    fn receive_rdm(&mut self) -> Result<RdmData, DmxError<Self::DriverError>> {
+        self.driver.receive_rdm()
+    }
+
+    fn receive_rdm_discovery_response(
+        &mut self,
+    ) -> Result<DiscoveryOption, DmxError<Self::DriverError>> {
+        self.driver.receive_rdm_discovery_response()
+    }
+
+    fn send_rdm_discovery_response(
+        &mut self,
+        uid: UniqueIdentifier,
+    ) -> Result<(), DmxError<Self::DriverError>> {
+        self.driver.send_rdm_discovery_response(uid)
+    }
+}
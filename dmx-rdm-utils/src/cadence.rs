@@ -0,0 +1,61 @@
+use dmx_rdm::dmx_driver::{DmxControllerDriver, DmxError};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Wraps a [DmxControllerDriver] and sends frames at a fixed cadence.
+///
+/// Every frame is scheduled one `interval` after the previous one was scheduled, not after it
+/// was sent, so the time spent preparing and sending frames doesn't add up to drift. If the
+/// caller falls behind by more than one interval the schedule restarts instead of sending a
+/// burst of frames to catch up.
+pub struct Cadence<D: DmxControllerDriver> {
+    driver: D,
+    interval: Duration,
+    next_send: Option<Instant>,
+}
+
+impl<D: DmxControllerDriver> Cadence<D> {
+    pub fn new(driver: D, interval: Duration) -> Self {
+        Self {
+            driver,
+            interval,
+            next_send: None,
+        }
+    }
+
+    /// Sleeps until the next frame is due and sends the frame. The first frame is sent right
+    /// away.
+    pub fn send_at_cadence(&mut self, frame: &[u8]) -> Result<(), DmxError<D::DriverError>> {
+        let now = Instant::now();
+        let scheduled = match self.next_send {
+            // restart the schedule if a whole interval was missed
+            Some(next_send) if now < next_send + self.interval => next_send,
+            _ => now,
+        };
+
+        sleep(scheduled.saturating_duration_since(now));
+        self.next_send = Some(scheduled + self.interval);
+
+        self.driver.send_dmx_package(frame)
+    }
+
+    /// Returns the interval between two frames.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Restarts the schedule, so the next frame is sent right away.
+    pub fn reset(&mut self) {
+        self.next_send = None;
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    /// Returns the underlying driver.
+    pub fn into_driver(self) -> D {
+        self.driver
+    }
+}
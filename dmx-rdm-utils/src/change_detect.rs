@@ -0,0 +1,57 @@
+use dmx_rdm::dmx_driver::{DmxControllerDriver, DmxError};
+use std::time::{Duration, Instant};
+
+/// Wraps a [DmxControllerDriver] and only transmits frames that differ from the last one sent.
+///
+/// Most fixtures blackout or hold after not receiving a frame for a while, so an unchanged frame
+/// is still resent once `max_idle` has passed.
+pub struct ChangeDetectSender<D: DmxControllerDriver> {
+    driver: D,
+    max_idle: Duration,
+    last_frame: heapless::Vec<u8, 512>,
+    last_sent: Option<Instant>,
+}
+
+impl<D: DmxControllerDriver> ChangeDetectSender<D> {
+    pub fn new(driver: D, max_idle: Duration) -> Self {
+        Self {
+            driver,
+            max_idle,
+            last_frame: heapless::Vec::new(),
+            last_sent: None,
+        }
+    }
+
+    /// Sends the frame if it differs from the last one or `max_idle` passed since the last
+    /// transmission. Returns whether the frame was transmitted.
+    pub fn send_if_changed(&mut self, frame: &[u8]) -> Result<bool, DmxError<D::DriverError>> {
+        let is_due = match self.last_sent {
+            None => true,
+            Some(last_sent) => {
+                frame != self.last_frame.as_slice() || last_sent.elapsed() >= self.max_idle
+            }
+        };
+
+        if !is_due {
+            return Ok(false);
+        }
+
+        let frame_copy = heapless::Vec::from_slice(frame).or(Err(DmxError::UartOverflow))?;
+        self.driver.send_dmx_package(frame)?;
+
+        self.last_frame = frame_copy;
+        self.last_sent = Some(Instant::now());
+
+        Ok(true)
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    /// Returns the underlying driver.
+    pub fn into_driver(self) -> D {
+        self.driver
+    }
+}
@@ -0,0 +1,159 @@
+use dmx_rdm::consts::{PREAMBLE_BYTE, SEPARATOR_BYTE};
+use dmx_rdm::unique_identifier::{PackageAddress, UniqueIdentifier};
+
+/// The maximum amount of preamble bytes in front of the separator of a discovery response.
+const MAX_PREAMBLE_SIZE: usize = 7;
+/// The encoded uid and checksum of a discovery response.
+const ENCODED_RESPONSE_SIZE: usize = 16;
+
+/// Calculates the rdm checksum of `data`, which is the sum of all bytes modulo 2^16.
+///
+/// For an rdm package `data` covers everything from the start code up to the parameter data.
+/// For a discovery response it covers the 12 encoded uid bytes after the separator.
+pub fn rdm_checksum(data: &[u8]) -> u16 {
+    data.iter()
+        .fold(0u16, |checksum, &byte| checksum.wrapping_add(byte as u16))
+}
+
+/// Decodes the response to a DISC_UNIQUE_BRANCH request, which consists of up to 7 preamble
+/// bytes (`0xFE`), the separator (`0xAA`) and the uid and checksum with every byte encoded as
+/// two bytes (`byte | 0xAA`, `byte | 0x55`).
+///
+/// Returns `None` if the response is malformed, the checksum doesn't match, the encoding bits
+/// aren't set or bytes follow the checksum, which is what colliding responses usually look like.
+pub fn decode_dub_response(response: &[u8]) -> Option<UniqueIdentifier> {
+    let preamble_size = response
+        .iter()
+        .take(MAX_PREAMBLE_SIZE)
+        .take_while(|&&byte| byte == PREAMBLE_BYTE)
+        .count();
+    if response.get(preamble_size) != Some(&SEPARATOR_BYTE) {
+        return None;
+    }
+
+    let encoded = &response[preamble_size + 1..];
+    if encoded.len() != ENCODED_RESPONSE_SIZE {
+        return None;
+    }
+
+    let mut decoded = [0u8; ENCODED_RESPONSE_SIZE / 2];
+    for (decoded_byte, pair) in decoded.iter_mut().zip(encoded.chunks_exact(2)) {
+        if pair[0] & 0xAA != 0xAA || pair[1] & 0x55 != 0x55 {
+            return None;
+        }
+
+        *decoded_byte = pair[0] & pair[1];
+    }
+
+    let received_checksum = u16::from_be_bytes([decoded[6], decoded[7]]);
+    if rdm_checksum(&encoded[..12]) != received_checksum {
+        return None;
+    }
+
+    match PackageAddress::from_bytes(decoded[..6].try_into().ok()?) {
+        PackageAddress::Device(uid) => Some(uid),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The response of the device 1234:56789ABC without a preamble, encoded as described in
+    /// section 7.5 of E1.20.
+    const ENCODED_RESPONSE: [u8; ENCODED_RESPONSE_SIZE] = [
+        0xBA, 0x57, 0xBE, 0x75, 0xFE, 0x57, 0xFA, 0x7D, 0xBA, 0xDF, 0xBE, 0xFD, // uid
+        0xAA, 0x5D, 0xEE, 0x75, // checksum 0x0864
+    ];
+
+    fn dub_response(preamble_size: usize, encoded: &[u8]) -> ([u8; 32], usize) {
+        let mut response = [0u8; 32];
+        response[..preamble_size].fill(PREAMBLE_BYTE);
+        response[preamble_size] = SEPARATOR_BYTE;
+        response[preamble_size + 1..][..encoded.len()].copy_from_slice(encoded);
+
+        (response, preamble_size + 1 + encoded.len())
+    }
+
+    #[test]
+    fn checksum_of_get_device_info_request() {
+        let request = [
+            0xCC, 0x01, 0x18, // start code, sub start code, message length
+            0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, // destination uid
+            0xCB, 0xA9, 0x87, 0x65, 0x43, 0x21, // source uid
+            0x00, 0x01, 0x00, 0x00, 0x00, // tn, port id, message count, sub device
+            0x20, 0x00, 0x60, 0x00, // GET_COMMAND, DEVICE_INFO, pdl
+        ];
+
+        assert_eq!(rdm_checksum(&request), 0x0694);
+    }
+
+    #[test]
+    fn checksum_wraps_around() {
+        assert_eq!(rdm_checksum(&[]), 0);
+        assert_eq!(rdm_checksum(&[0xFF; 300]), (300 * 0xFF % 0x10000) as u16);
+    }
+
+    #[test]
+    fn checksum_of_encoded_uid() {
+        assert_eq!(rdm_checksum(&ENCODED_RESPONSE[..12]), 0x0864);
+    }
+
+    #[test]
+    fn decodes_response_with_any_preamble_size() {
+        let expected = UniqueIdentifier::new(0x1234, 0x56789ABC).unwrap();
+
+        for preamble_size in 0..=MAX_PREAMBLE_SIZE {
+            let (response, size) = dub_response(preamble_size, &ENCODED_RESPONSE);
+            assert_eq!(decode_dub_response(&response[..size]), Some(expected));
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_checksum() {
+        let mut encoded = ENCODED_RESPONSE;
+        encoded[15] = 0x77;
+        let (response, size) = dub_response(7, &encoded);
+
+        assert_eq!(decode_dub_response(&response[..size]), None);
+    }
+
+    #[test]
+    fn rejects_missing_encoding_bits() {
+        let mut encoded = ENCODED_RESPONSE;
+        // Swapping a pair keeps the decoded value and the checksum, only the encoding is wrong.
+        encoded.swap(0, 1);
+        let (response, size) = dub_response(7, &encoded);
+
+        assert_eq!(decode_dub_response(&response[..size]), None);
+    }
+
+    #[test]
+    fn rejects_malformed_responses() {
+        // no separator
+        assert_eq!(decode_dub_response(&[PREAMBLE_BYTE; 24]), None);
+
+        // too many preamble bytes
+        let (response, size) = dub_response(8, &ENCODED_RESPONSE);
+        assert_eq!(decode_dub_response(&response[..size]), None);
+
+        // truncated
+        let (response, size) = dub_response(7, &ENCODED_RESPONSE[..15]);
+        assert_eq!(decode_dub_response(&response[..size]), None);
+
+        // trailing bytes
+        let (response, size) = dub_response(7, &ENCODED_RESPONSE);
+        assert_eq!(decode_dub_response(&response[..size + 1]), None);
+    }
+
+    #[test]
+    fn rejects_broadcast_uid() {
+        let mut encoded = [0xFF; ENCODED_RESPONSE_SIZE];
+        // checksum 0x0BF4 of twelve 0xFF bytes
+        encoded[12..].copy_from_slice(&[0xAB, 0x5F, 0xFE, 0xF5]);
+        let (response, size) = dub_response(7, &encoded);
+
+        assert_eq!(decode_dub_response(&response[..size]), None);
+    }
+}
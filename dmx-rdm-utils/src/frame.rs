@@ -0,0 +1,73 @@
+use crate::start_code::StartCode;
+use core::fmt::Formatter;
+use dmx_rdm::consts::DMX_MAX_PACKAGE_SIZE;
+
+/// The reason why a frame is not a valid dmx512 frame.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FrameError {
+    /// The frame doesn't contain any slots.
+    Empty,
+    /// The frame contains more than 512 slots.
+    TooLong,
+    /// The frame uses the rdm start code. Rdm packages have to be sent using the rdm functions
+    /// of the driver.
+    RdmStartCode,
+}
+
+impl core::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FrameError::Empty => write!(f, "frame doesn't contain any slots"),
+            FrameError::TooLong => write!(f, "frame contains more than 512 slots"),
+            FrameError::RdmStartCode => write!(f, "frame uses the rdm start code"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+/// Checks that `data` (the slots without the start code) fits into a dmx512 frame
+/// together with the `start_code`.
+pub fn validate_dmx_frame(start_code: StartCode, data: &[u8]) -> Result<(), FrameError> {
+    if start_code == StartCode::Rdm {
+        return Err(FrameError::RdmStartCode);
+    }
+
+    if data.is_empty() {
+        return Err(FrameError::Empty);
+    }
+
+    if data.len() + 1 > DMX_MAX_PACKAGE_SIZE {
+        return Err(FrameError::TooLong);
+    }
+
+    Ok(())
+}
+
+/// How drivers handle frames with more than 512 slots after the start code when sending.
+///
+/// Such frames are invalid dmx. They are usually the result of a buffer of 513 channels that
+/// was sent without accounting for the start code.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum SlotCountValidation {
+    /// Reject the frame with the frame too long error of the driver without sending anything.
+    #[default]
+    Strict,
+    /// Send the first 512 slots and drop the rest. Drivers log a warning if they support
+    /// logging.
+    Lenient,
+}
+
+/// Calculates a 32 bit fingerprint (FNV-1a) of a frame. Identical frames always have the same
+/// fingerprint, so comparing fingerprints is a cheap way to detect changed frames.
+///
+/// This isn't cryptographically secure and different frames can collide.
+pub fn frame_fingerprint(frame: &[u8]) -> u32 {
+    const FNV_OFFSET_BASIS: u32 = 0x811C_9DC5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+
+    frame.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u32).wrapping_mul(FNV_PRIME)
+    })
+}
@@ -0,0 +1,86 @@
+use dmx_rdm::dmx_driver::{DmxControllerDriver, DmxError, RdmControllerDriver};
+use dmx_rdm::rdm_data::RdmData;
+use std::time::{Duration, Instant};
+
+/// Wraps a driver and interleaves rdm transactions with continuous dmx output.
+///
+/// The last frame sent by [RdmInterleaver::send_dmx] is buffered and resent right after every
+/// rdm transaction, so the output only pauses for the transaction itself. A transaction takes a
+/// few ms at most (the request plus the response timeout), which fixtures don't notice.
+///
+/// Only use this for GET and SET requests. Discovery has to handle collisions and should be
+/// done using the driver directly.
+pub struct RdmInterleaver<D: DmxControllerDriver + RdmControllerDriver> {
+    driver: D,
+    frame: heapless::Vec<u8, 512>,
+    last_frame_sent: Option<Instant>,
+}
+
+impl<D: DmxControllerDriver + RdmControllerDriver> RdmInterleaver<D> {
+    pub fn new(driver: D) -> Self {
+        Self {
+            driver,
+            frame: heapless::Vec::new(),
+            last_frame_sent: None,
+        }
+    }
+
+    /// Sends the frame and keeps it for resending after rdm transactions.
+    pub fn send_dmx(&mut self, frame: &[u8]) -> Result<(), DmxError<D::DriverError>> {
+        let frame_copy = heapless::Vec::from_slice(frame).or(Err(DmxError::UartOverflow))?;
+        self.driver.send_dmx_package(frame)?;
+
+        self.frame = frame_copy;
+        self.last_frame_sent = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Sends an rdm request, waits for its response and resends the last dmx frame right
+    /// afterwards. Broadcast requests aren't answered and return `Ok(None)`.
+    ///
+    /// The frame is resent even if the transaction failed, so a missing response doesn't
+    /// cause a dropout. An error while resending the frame takes precedence over the result of
+    /// the transaction.
+    pub fn transact_rdm(
+        &mut self,
+        request: RdmData,
+    ) -> Result<Option<RdmData>, DmxError<D::DriverError>> {
+        let is_broadcast = matches!(
+            request,
+            RdmData::Request(ref request) if request.destination_uid.is_broadcast()
+        );
+
+        let result = self
+            .driver
+            .send_rdm(request)
+            .and_then(|_| match is_broadcast {
+                true => Ok(None),
+                false => self.driver.receive_rdm().map(Some),
+            });
+
+        if !self.frame.is_empty() {
+            self.driver.send_dmx_package(&self.frame)?;
+            self.last_frame_sent = Some(Instant::now());
+        }
+
+        result
+    }
+
+    /// Returns the time since the last dmx frame was sent or `None` if no frame was sent yet.
+    /// Use this to check that the output stays within the refresh tolerance of the fixtures.
+    pub fn time_since_last_frame(&self) -> Option<Duration> {
+        self.last_frame_sent
+            .map(|last_frame_sent| last_frame_sent.elapsed())
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    /// Returns the underlying driver.
+    pub fn into_driver(self) -> D {
+        self.driver
+    }
+}
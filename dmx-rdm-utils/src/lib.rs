@@ -0,0 +1,37 @@
+//! Driver independent utilities for the drivers of the [dmx-rdm-rs](https://crates.io/crates/dmx-rdm)
+//! library. Everything in here works with any driver implementing the dmx-rdm traits.
+//!
+//! The `std` feature is enabled by default. Disable it for usage on embedded.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Controller driver that can hold any driver, for picking the driver at runtime.
+#[cfg(feature = "std")]
+pub mod boxed;
+/// Sender that transmits frames at a fixed cadence without drifting.
+#[cfg(feature = "std")]
+pub mod cadence;
+/// Sender that skips transmitting frames that didn't change.
+#[cfg(feature = "std")]
+pub mod change_detect;
+/// Rdm checksums and decoding of discovery responses for drivers without rdm firmware.
+pub mod checksum;
+/// Validation and fingerprinting of frames.
+pub mod frame;
+/// Interleaving of rdm transactions with continuous dmx output.
+#[cfg(feature = "std")]
+pub mod interleave;
+/// Merging of two sources by highest or latest takes precedence.
+pub mod merge;
+/// Sender that drops frames exceeding a maximum refresh rate.
+#[cfg(feature = "std")]
+pub mod rate_limit;
+/// Measurement of the achieved refresh rate of a driver.
+#[cfg(feature = "std")]
+pub mod refresh_rate;
+/// Forwarding of received frames to other drivers.
+pub mod repeater;
+/// Named dmx512 start codes.
+pub mod start_code;
+/// Plausibility checks for uids.
+pub mod uid;
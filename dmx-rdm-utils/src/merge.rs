@@ -0,0 +1,157 @@
+/// The amount of channels of a dmx universe.
+const UNIVERSE_SIZE: usize = 512;
+
+/// Merges two sources by highest takes precedence: every channel of `out` gets the higher value
+/// of `a` and `b`.
+///
+/// Channels that are missing in one of the sources count as zero, so `out` can be longer than
+/// both sources. Channels beyond the length of `out` are ignored.
+pub fn merge_htp(a: &[u8], b: &[u8], out: &mut [u8]) {
+    for (index, channel) in out.iter_mut().enumerate() {
+        *channel = channel_value(a, index).max(channel_value(b, index));
+    }
+}
+
+/// The source a channel of the [LtpMerger] is taken from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Source {
+    A,
+    B,
+}
+
+/// Merges two sources by latest takes precedence: every channel gets the value of the source
+/// that changed it last.
+///
+/// The merger keeps the previous frames of both sources to detect the changes, so it has to be
+/// fed every frame of both sources. Channels that are missing in a source count as zero, so a
+/// source that gets shorter changes its missing channels to zero.
+pub struct LtpMerger {
+    previous_a: [u8; UNIVERSE_SIZE],
+    previous_b: [u8; UNIVERSE_SIZE],
+    sources: [Source; UNIVERSE_SIZE],
+}
+
+impl LtpMerger {
+    /// Creates a merger that starts with both sources at zero and every channel taken from `a`.
+    pub fn new() -> Self {
+        Self {
+            previous_a: [0; UNIVERSE_SIZE],
+            previous_b: [0; UNIVERSE_SIZE],
+            sources: [Source::A; UNIVERSE_SIZE],
+        }
+    }
+
+    /// Merges the current frames (channels without the start code) of both sources into `out`.
+    ///
+    /// If both sources change a channel at the same time, `b` takes precedence. Channels beyond
+    /// the length of `out` aren't merged and channels beyond 512 are set to zero.
+    pub fn merge(&mut self, a: &[u8], b: &[u8], out: &mut [u8]) {
+        for index in 0..UNIVERSE_SIZE {
+            let value_a = channel_value(a, index);
+            let value_b = channel_value(b, index);
+
+            if value_b != self.previous_b[index] {
+                self.sources[index] = Source::B;
+            } else if value_a != self.previous_a[index] {
+                self.sources[index] = Source::A;
+            }
+
+            self.previous_a[index] = value_a;
+            self.previous_b[index] = value_b;
+        }
+
+        for (index, channel) in out.iter_mut().enumerate() {
+            *channel = match self.sources.get(index) {
+                Some(Source::A) => self.previous_a[index],
+                Some(Source::B) => self.previous_b[index],
+                None => 0,
+            };
+        }
+    }
+
+    /// Forgets the previous frames, as if both sources were at zero.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for LtpMerger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn channel_value(source: &[u8], index: usize) -> u8 {
+    source.get(index).copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn htp_takes_the_higher_value() {
+        let mut out = [0xFF; 4];
+        merge_htp(&[10, 200, 30], &[20, 100], &mut out);
+
+        assert_eq!(out, [20, 200, 30, 0]);
+    }
+
+    #[test]
+    fn htp_ignores_channels_beyond_out() {
+        let mut out = [0; 2];
+        merge_htp(&[1, 2, 3], &[4, 5, 6, 7], &mut out);
+
+        assert_eq!(out, [4, 5]);
+    }
+
+    #[test]
+    fn ltp_takes_the_source_that_changed_last() {
+        let mut merger = LtpMerger::new();
+        let mut out = [0; 3];
+
+        merger.merge(&[10, 10, 10], &[], &mut out);
+        assert_eq!(out, [10, 10, 10]);
+
+        // b only changes the second channel, the others stay with a.
+        merger.merge(&[10, 10, 10], &[0, 50], &mut out);
+        assert_eq!(out, [10, 50, 10]);
+
+        // a changes the second channel again, so it takes it back.
+        merger.merge(&[10, 20, 10], &[0, 50], &mut out);
+        assert_eq!(out, [10, 20, 10]);
+
+        // Both change the first channel, which goes to b.
+        merger.merge(&[30, 20, 10], &[40, 50], &mut out);
+        assert_eq!(out, [40, 20, 10]);
+    }
+
+    #[test]
+    fn ltp_treats_missing_channels_as_zero() {
+        let mut merger = LtpMerger::new();
+        let mut out = [0xFF; 4];
+
+        merger.merge(&[10, 20], &[0, 0, 30], &mut out);
+        assert_eq!(out, [10, 20, 30, 0]);
+
+        // b got shorter, which changes its third channel to zero.
+        merger.merge(&[10, 20], &[0, 0], &mut out);
+        assert_eq!(out, [10, 20, 0, 0]);
+    }
+
+    #[test]
+    fn ltp_reset_forgets_the_previous_frames() {
+        let mut merger = LtpMerger::new();
+        let mut out = [0; 1];
+
+        merger.merge(&[10], &[20], &mut out);
+        assert_eq!(out, [20]);
+
+        merger.reset();
+        merger.merge(&[10], &[20], &mut out);
+        assert_eq!(out, [20]);
+
+        merger.merge(&[30], &[20], &mut out);
+        assert_eq!(out, [30]);
+    }
+}
@@ -0,0 +1,95 @@
+use dmx_rdm::dmx_driver::{DmxControllerDriver, DmxError};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Wraps a [DmxControllerDriver] and transmits at most one frame per `min_period`.
+///
+/// Frames that are sent faster are dropped instead of piling up in the buffers of the driver,
+/// so the latency doesn't grow if the caller sends faster than the output can keep up with.
+/// The newest dropped frame is kept and sent by the next call once the period passed or by
+/// [RateLimited::flush].
+pub struct RateLimited<D: DmxControllerDriver> {
+    driver: D,
+    min_period: Duration,
+    pending_frame: Option<heapless::Vec<u8, 512>>,
+    last_sent: Option<Instant>,
+}
+
+impl<D: DmxControllerDriver> RateLimited<D> {
+    pub fn new(driver: D, min_period: Duration) -> Self {
+        Self {
+            driver,
+            min_period,
+            pending_frame: None,
+            last_sent: None,
+        }
+    }
+
+    /// Creates a wrapper that sends at most `max_rate_hz` frames per second.
+    ///
+    /// A rate of `0` doesn't limit the rate at all.
+    pub fn with_max_rate(driver: D, max_rate_hz: u32) -> Self {
+        let min_period = match max_rate_hz {
+            0 => Duration::ZERO,
+            max_rate_hz => Duration::from_secs(1) / max_rate_hz,
+        };
+
+        Self::new(driver, min_period)
+    }
+
+    /// Sends the frame if `min_period` passed since the last transmission. Otherwise the frame
+    /// replaces the pending frame. Returns whether the frame was transmitted.
+    pub fn send(&mut self, frame: &[u8]) -> Result<bool, DmxError<D::DriverError>> {
+        if self.time_until_due() != Duration::ZERO {
+            self.pending_frame =
+                Some(heapless::Vec::from_slice(frame).or(Err(DmxError::UartOverflow))?);
+            return Ok(false);
+        }
+
+        self.transmit(frame)?;
+
+        Ok(true)
+    }
+
+    /// Waits until the next frame is due and sends the pending frame. Returns whether there was
+    /// a pending frame to send.
+    ///
+    /// Call this after the last [RateLimited::send] so the final frame doesn't get lost.
+    pub fn flush(&mut self) -> Result<bool, DmxError<D::DriverError>> {
+        let pending_frame = match self.pending_frame.take() {
+            Some(pending_frame) => pending_frame,
+            None => return Ok(false),
+        };
+
+        sleep(self.time_until_due());
+        self.transmit(&pending_frame)?;
+
+        Ok(true)
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    /// Returns the underlying driver. A pending frame is discarded.
+    pub fn into_driver(self) -> D {
+        self.driver
+    }
+
+    fn time_until_due(&self) -> Duration {
+        match self.last_sent {
+            None => Duration::ZERO,
+            Some(last_sent) => self.min_period.saturating_sub(last_sent.elapsed()),
+        }
+    }
+
+    fn transmit(&mut self, frame: &[u8]) -> Result<(), DmxError<D::DriverError>> {
+        self.driver.send_dmx_package(frame)?;
+
+        self.pending_frame = None;
+        self.last_sent = Some(Instant::now());
+
+        Ok(())
+    }
+}
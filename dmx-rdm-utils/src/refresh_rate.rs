@@ -0,0 +1,72 @@
+use dmx_rdm::dmx_driver::{ControllerDriverErrorDef, DmxControllerDriver, DmxError};
+use std::time::{Duration, Instant};
+
+/// Wraps a [DmxControllerDriver] and measures the rate at which frames are actually sent.
+///
+/// Drivers can block while sending (e.g. waiting for the previous frame), so the achieved rate
+/// is often lower than the rate of the loop that calls them. Only frames that were sent
+/// successfully are counted.
+pub struct RefreshRateMeter<D: DmxControllerDriver> {
+    driver: D,
+    frames_sent: u32,
+    window_start: Instant,
+}
+
+impl<D: DmxControllerDriver> RefreshRateMeter<D> {
+    pub fn new(driver: D) -> Self {
+        Self {
+            driver,
+            frames_sent: 0,
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Returns the average amount of frames sent per second since the last reset.
+    pub fn fps(&self) -> f32 {
+        let elapsed = self.elapsed();
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+
+        self.frames_sent as f32 / elapsed.as_secs_f32()
+    }
+
+    /// Returns the amount of frames sent since the last reset.
+    pub fn frames_sent(&self) -> u32 {
+        self.frames_sent
+    }
+
+    /// Returns the time since the last reset.
+    pub fn elapsed(&self) -> Duration {
+        self.window_start.elapsed()
+    }
+
+    /// Starts a new measurement window.
+    pub fn reset(&mut self) {
+        self.frames_sent = 0;
+        self.window_start = Instant::now();
+    }
+
+    /// Get a reference to the underlying driver.
+    pub fn get_driver(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    /// Returns the underlying driver.
+    pub fn into_driver(self) -> D {
+        self.driver
+    }
+}
+
+impl<D: DmxControllerDriver> ControllerDriverErrorDef for RefreshRateMeter<D> {
+    type DriverError = D::DriverError;
+}
+
+impl<D: DmxControllerDriver> DmxControllerDriver for RefreshRateMeter<D> {
+    fn send_dmx_package(&mut self, package: &[u8]) -> Result<(), DmxError<Self::DriverError>> {
+        self.driver.send_dmx_package(package)?;
+        self.frames_sent = self.frames_sent.saturating_add(1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+use crate::start_code::StartCode;
+use core::fmt::Formatter;
+use dmx_rdm::consts::DMX_MAX_PACKAGE_SIZE;
+use dmx_rdm::dmx_uart_driver::{DmxRecvUartDriver, DmxRespUartDriver, DmxUartDriverError};
+
+/// An error that occurred while repeating a frame.
+#[derive(Debug)]
+pub enum RepeaterError<InputError, OutputError> {
+    /// Receiving the frame failed.
+    Input(DmxUartDriverError<InputError>),
+    /// Sending the frame to the output with the given index failed. The outputs before it
+    /// already received the frame, the ones after it didn't.
+    Output(usize, DmxUartDriverError<OutputError>),
+}
+
+impl<InputError: core::fmt::Display, OutputError: core::fmt::Display> core::fmt::Display
+    for RepeaterError<InputError, OutputError>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RepeaterError::Input(error) => write!(f, "receiving failed: {}", error),
+            RepeaterError::Output(index, error) => {
+                write!(f, "sending to output {} failed: {}", index, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<
+        InputError: core::fmt::Debug + core::fmt::Display,
+        OutputError: core::fmt::Debug + core::fmt::Display,
+    > std::error::Error for RepeaterError<InputError, OutputError>
+{
+}
+
+/// Receives dmx frames from one driver and sends them to up to `N` outputs, e.g. for building
+/// a splitter or a repeater.
+///
+/// Rdm packages aren't forwarded, since their responses would have to travel back to the
+/// controller.
+///
+/// The channels of dmx frames can be remapped using [Repeater::set_patch].
+pub struct Repeater<I: DmxRecvUartDriver, O: DmxRespUartDriver, const N: usize> {
+    input: I,
+    outputs: heapless::Vec<O, N>,
+    patch: heapless::Vec<u16, 512>,
+    patched_frame_size: usize,
+}
+
+impl<I: DmxRecvUartDriver, O: DmxRespUartDriver, const N: usize> Repeater<I, O, N> {
+    pub fn new(input: I) -> Self {
+        Self {
+            input,
+            outputs: heapless::Vec::new(),
+            patch: heapless::Vec::new(),
+            patched_frame_size: 0,
+        }
+    }
+
+    /// Remaps the channels of dmx frames (null start code) before they are sent. Entry `i`
+    /// of `patch` is the output channel (1 to 512) for input channel `i + 1`. An empty patch
+    /// forwards the frames unchanged, which is the default.
+    ///
+    /// Entries that are `0` or bigger than 512 are ignored, as well as entries beyond the
+    /// first 512. If several input channels are patched to the same output channel, the
+    /// highest value is sent. Output channels without an input are sent as `0`. The output
+    /// frame ends with the highest patched output channel.
+    ///
+    /// Patching gathers every channel of the patch for each frame, which takes a few µs for
+    /// a full universe on a microcontroller.
+    pub fn set_patch(&mut self, patch: &[u16]) {
+        self.patch.clear();
+        // Can't fail, since the patch is limited to the capacity.
+        self.patch
+            .extend_from_slice(&patch[..patch.len().min(512)])
+            .ok();
+
+        self.patched_frame_size = self
+            .patch
+            .iter()
+            .filter(|&&output_channel| (1..=512).contains(&output_channel))
+            .max()
+            .map_or(0, |&output_channel| output_channel as usize + 1);
+    }
+
+    /// Adds an output that receives all following frames. Returns the output again if there
+    /// are already `N` outputs.
+    pub fn add_output(&mut self, output: O) -> Result<(), O> {
+        self.outputs.push(output)
+    }
+
+    /// Waits up to `timeout_us` for a frame and sends it to all outputs.
+    ///
+    /// Returns the amount of slots that were forwarded including the start code or `0` if no
+    /// frame was forwarded.
+    pub fn pump(
+        &mut self,
+        timeout_us: u32,
+    ) -> Result<usize, RepeaterError<I::DriverError, O::DriverError>> {
+        let mut frame = [0u8; DMX_MAX_PACKAGE_SIZE];
+        let frame_size = match self.input.read_frames(&mut frame, timeout_us) {
+            Ok(frame_size) => frame_size,
+            Err(DmxUartDriverError::TimeoutError) => return Ok(0),
+            Err(error) => return Err(RepeaterError::Input(error)),
+        };
+
+        let mut frame = &frame[..frame_size];
+        let start_code = match frame.first() {
+            Some(&start_code) => StartCode::from(start_code),
+            None => return Ok(0),
+        };
+        if start_code == StartCode::Rdm {
+            return Ok(0);
+        }
+
+        let mut patched_frame = [0u8; DMX_MAX_PACKAGE_SIZE];
+        if start_code == StartCode::Null && !self.patch.is_empty() {
+            frame = self.apply_patch(frame, &mut patched_frame);
+        }
+
+        for (index, output) in self.outputs.iter_mut().enumerate() {
+            output
+                .write_frames(frame)
+                .map_err(|error| RepeaterError::Output(index, error))?;
+        }
+
+        Ok(frame.len())
+    }
+
+    /// Writes the patched `frame` into `patched_frame` and returns the used part of it.
+    fn apply_patch<'b>(&self, frame: &[u8], patched_frame: &'b mut [u8]) -> &'b [u8] {
+        patched_frame[0] = frame[0];
+
+        for (input_slot, &output_channel) in (1..frame.len()).zip(self.patch.iter()) {
+            let output_slot = output_channel as usize;
+            if !(1..=512).contains(&output_slot) {
+                continue;
+            }
+
+            patched_frame[output_slot] = patched_frame[output_slot].max(frame[input_slot]);
+        }
+
+        &patched_frame[..self.patched_frame_size.max(1)]
+    }
+
+    /// Get a reference to the input driver.
+    pub fn get_input(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Get a reference to the output drivers.
+    pub fn get_outputs(&mut self) -> &mut [O] {
+        &mut self.outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dmx_rdm::consts::SC_RDM;
+    use dmx_rdm::dmx_uart_driver::DmxUartDriver;
+
+    const TIMEOUT_US: u32 = 1_000;
+
+    /// Input that hands out the frames one after another and times out afterwards.
+    struct ScriptedInput<'a> {
+        frames: core::slice::Iter<'a, &'a [u8]>,
+    }
+
+    impl DmxUartDriver for ScriptedInput<'_> {
+        type DriverError = ();
+    }
+
+    impl DmxRecvUartDriver for ScriptedInput<'_> {
+        fn read_frames(
+            &mut self,
+            buffer: &mut [u8],
+            _timeout_us: u32,
+        ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+            let frame = self.frames.next().ok_or(DmxUartDriverError::TimeoutError)?;
+            buffer[..frame.len()].copy_from_slice(frame);
+
+            Ok(frame.len())
+        }
+
+        fn read_frames_no_break(
+            &mut self,
+            _buffer: &mut [u8],
+            _timeout_us: u32,
+        ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+            Err(DmxUartDriverError::TimeoutError)
+        }
+    }
+
+    /// Output that keeps the written frames or fails every write.
+    #[derive(Default)]
+    struct RecordingOutput {
+        frames: heapless::Vec<heapless::Vec<u8, DMX_MAX_PACKAGE_SIZE>, 4>,
+        failing: bool,
+    }
+
+    impl DmxUartDriver for RecordingOutput {
+        type DriverError = ();
+    }
+
+    impl DmxRespUartDriver for RecordingOutput {
+        fn write_frames(
+            &mut self,
+            buffer: &[u8],
+        ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+            if self.failing {
+                return Err(DmxUartDriverError::DriverError(()));
+            }
+
+            self.frames
+                .push(heapless::Vec::from_slice(buffer).unwrap())
+                .unwrap();
+
+            Ok(buffer.len())
+        }
+
+        fn write_frames_no_break(
+            &mut self,
+            buffer: &[u8],
+        ) -> Result<usize, DmxUartDriverError<Self::DriverError>> {
+            self.write_frames(buffer)
+        }
+    }
+
+    /// Repeats `frame` to a single output using `patch` and returns what the output received.
+    fn repeat(patch: &[u16], frame: &[u8]) -> heapless::Vec<u8, DMX_MAX_PACKAGE_SIZE> {
+        let frames = [frame];
+        let mut repeater: Repeater<_, _, 1> = Repeater::new(ScriptedInput {
+            frames: frames.iter(),
+        });
+        repeater
+            .add_output(RecordingOutput::default())
+            .ok()
+            .unwrap();
+        repeater.set_patch(patch);
+
+        repeater.pump(TIMEOUT_US).unwrap();
+        repeater.get_outputs()[0].frames.pop().unwrap()
+    }
+
+    #[test]
+    fn patch_remaps_channels_and_zero_fills_the_rest() {
+        // The frame ends with output channel 3, channel 2 has no input.
+        let repeated_frame = repeat(&[3, 1], &[0x00, 10, 20]);
+        assert_eq!(&repeated_frame[..], &[0x00, 20, 0, 10]);
+
+        // Input channels without a patch entry are dropped.
+        let repeated_frame = repeat(&[1], &[0x00, 10, 20]);
+        assert_eq!(&repeated_frame[..], &[0x00, 10]);
+    }
+
+    #[test]
+    fn patch_ignores_entries_out_of_range() {
+        let repeated_frame = repeat(&[0, 2, 513], &[0x00, 10, 20, 30]);
+        assert_eq!(&repeated_frame[..], &[0x00, 0, 20]);
+    }
+
+    #[test]
+    fn patch_sends_the_highest_value_on_collision() {
+        let repeated_frame = repeat(&[1, 1, 1], &[0x00, 10, 30, 20]);
+        assert_eq!(&repeated_frame[..], &[0x00, 30]);
+    }
+
+    #[test]
+    fn patch_only_applies_to_null_start_code() {
+        let frame = [0x17, 1, 2, 3];
+        let repeated_frame = repeat(&[3, 2, 1], &frame);
+        assert_eq!(&repeated_frame[..], &frame);
+    }
+
+    #[test]
+    fn pump_drops_rdm_frames() {
+        let frames: [&[u8]; 2] = [&[SC_RDM, 0x01, 24], &[0x00, 42]];
+        let mut repeater: Repeater<_, _, 1> = Repeater::new(ScriptedInput {
+            frames: frames.iter(),
+        });
+        repeater
+            .add_output(RecordingOutput::default())
+            .ok()
+            .unwrap();
+
+        assert_eq!(repeater.pump(TIMEOUT_US).unwrap(), 0);
+        assert!(repeater.get_outputs()[0].frames.is_empty());
+
+        assert_eq!(repeater.pump(TIMEOUT_US).unwrap(), 2);
+        assert_eq!(&repeater.get_outputs()[0].frames[0][..], &[0x00, 42]);
+    }
+
+    #[test]
+    fn pump_reports_the_index_of_the_failed_output() {
+        let frames: [&[u8]; 1] = [&[0x00, 42]];
+        let mut repeater: Repeater<_, _, 3> = Repeater::new(ScriptedInput {
+            frames: frames.iter(),
+        });
+        repeater
+            .add_output(RecordingOutput::default())
+            .ok()
+            .unwrap();
+        repeater
+            .add_output(RecordingOutput {
+                failing: true,
+                ..RecordingOutput::default()
+            })
+            .ok()
+            .unwrap();
+        repeater
+            .add_output(RecordingOutput::default())
+            .ok()
+            .unwrap();
+
+        assert!(matches!(
+            repeater.pump(TIMEOUT_US),
+            Err(RepeaterError::Output(
+                1,
+                DmxUartDriverError::DriverError(())
+            ))
+        ));
+
+        // The outputs in front of the failed one already received the frame.
+        let outputs = repeater.get_outputs();
+        assert_eq!(outputs[0].frames.len(), 1);
+        assert!(outputs[2].frames.is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+use dmx_rdm::consts::{DMX_NULL_START, SC_RDM};
+
+/// The start code (first slot) of a dmx512 package.
+///
+/// Converting an u8 always yields the named variant for known start codes, so
+/// `StartCode::Custom` only holds start codes without a variant.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum StartCode {
+    /// Regular dmx512 channel data (`0x00`).
+    Null,
+    /// ASCII text packet (`0x17`).
+    Text,
+    /// Test packet (`0x55`).
+    Test,
+    /// Remote device management (`0xCC`).
+    Rdm,
+    /// System information packet (`0xCF`).
+    SystemInformation,
+    /// Any other start code, e.g. a manufacturer specific one.
+    Custom(u8),
+}
+
+const SC_TEXT: u8 = 0x17;
+const SC_TEST: u8 = 0x55;
+const SC_SYSTEM_INFORMATION: u8 = 0xCF;
+
+impl From<u8> for StartCode {
+    fn from(value: u8) -> Self {
+        match value {
+            DMX_NULL_START => StartCode::Null,
+            SC_TEXT => StartCode::Text,
+            SC_TEST => StartCode::Test,
+            SC_RDM => StartCode::Rdm,
+            SC_SYSTEM_INFORMATION => StartCode::SystemInformation,
+            value => StartCode::Custom(value),
+        }
+    }
+}
+
+impl From<StartCode> for u8 {
+    fn from(value: StartCode) -> Self {
+        match value {
+            StartCode::Null => DMX_NULL_START,
+            StartCode::Text => SC_TEXT,
+            StartCode::Test => SC_TEST,
+            StartCode::Rdm => SC_RDM,
+            StartCode::SystemInformation => SC_SYSTEM_INFORMATION,
+            StartCode::Custom(value) => value,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use dmx_rdm::unique_identifier::UniqueIdentifier;
+
+/// Checks that `uid` could belong to a real device.
+///
+/// Line noise sometimes decodes into a valid discovery response. Uids that are all zeros or
+/// use the broadcast manufacturer or device id can't belong to a device, so they are most
+/// likely the result of such noise.
+pub fn is_plausible_uid(uid: &UniqueIdentifier) -> bool {
+    let all_zeros = uid.manufacturer_uid() == 0x0000 && uid.device_uid() == 0x0000_0000;
+
+    !all_zeros && uid.manufacturer_uid() != 0xFFFF && uid.device_uid() != 0xFFFF_FFFF
+}